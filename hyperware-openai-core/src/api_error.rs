@@ -0,0 +1,98 @@
+use http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// Everything an OpenAI error response says about what went wrong.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiErrorDetails {
+    pub status: u16,
+    pub message: String,
+    /// The `type` field, e.g. `invalid_request_error`.
+    pub error_type: Option<String>,
+    /// The `code` field, e.g. `insufficient_quota` or `rate_limit_exceeded`.
+    pub code: Option<String>,
+    /// The request parameter the server rejected.
+    pub param: Option<String>,
+    /// The `x-request-id` header, needed when contacting OpenAI support.
+    pub request_id: Option<String>,
+}
+
+/// Broad category of an API error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiErrorKind {
+    Authentication,
+    PermissionDenied,
+    RateLimited,
+    QuotaExceeded,
+    InvalidRequest,
+    ServerError,
+    Timeout,
+    Other,
+}
+
+impl ApiErrorDetails {
+    /// Reads an error response. Bodies that are not OpenAI error JSON become the message.
+    pub fn from_response(status: u16, headers: &HeaderMap, body: &[u8]) -> Self {
+        let request_id = headers
+            .get("x-request-id")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        let error = serde_json::from_slice::<Value>(body)
+            .ok()
+            .and_then(|mut json| json.get_mut("error").map(Value::take))
+            .filter(Value::is_object);
+        let field = |name: &str| {
+            error.as_ref().and_then(|error| match error.get(name)? {
+                Value::String(value) => Some(value.clone()),
+                Value::Null => None,
+                other => Some(other.to_string()),
+            })
+        };
+
+        Self {
+            status,
+            message: field("message")
+                .unwrap_or_else(|| String::from_utf8_lossy(body).to_string()),
+            error_type: field("type"),
+            code: field("code"),
+            param: field("param"),
+            request_id,
+        }
+    }
+
+    pub fn kind(&self) -> ApiErrorKind {
+        let is = |value: &str| {
+            self.code.as_deref() == Some(value) || self.error_type.as_deref() == Some(value)
+        };
+
+        match self.status {
+            401 => ApiErrorKind::Authentication,
+            403 => ApiErrorKind::PermissionDenied,
+            429 if is("insufficient_quota") => ApiErrorKind::QuotaExceeded,
+            429 => ApiErrorKind::RateLimited,
+            408 | 504 => ApiErrorKind::Timeout,
+            400 | 404 | 413 | 415 | 422 => ApiErrorKind::InvalidRequest,
+            500..=599 => ApiErrorKind::ServerError,
+            _ if is("invalid_request_error") => ApiErrorKind::InvalidRequest,
+            _ => ApiErrorKind::Other,
+        }
+    }
+}
+
+impl fmt::Display for ApiErrorDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} - {}", self.status, self.message)?;
+        if let Some(code) = &self.code {
+            write!(f, " (code: {})", code)?;
+        }
+        if let Some(param) = &self.param {
+            write!(f, " (param: {})", param)?;
+        }
+        if let Some(request_id) = &self.request_id {
+            write!(f, " (request id: {})", request_id)?;
+        }
+        Ok(())
+    }
+}
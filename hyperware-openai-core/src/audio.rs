@@ -0,0 +1,121 @@
+use std::fmt;
+
+/// The parts of a WAV file that splitting and joining need.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wav<'a> {
+    /// Body of the `fmt ` chunk.
+    pub fmt: &'a [u8],
+    pub sample_rate: u32,
+    /// Bytes per sample frame, all channels included.
+    pub block_align: usize,
+    pub samples: &'a [u8],
+}
+
+impl Wav<'_> {
+    /// Duration of the samples in seconds.
+    pub fn duration(&self) -> f64 {
+        (self.samples.len() / self.block_align) as f64 / self.sample_rate as f64
+    }
+}
+
+/// Why [`parse_wav`] rejected its input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidWav(pub &'static str);
+
+impl fmt::Display for InvalidWav {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid WAV audio: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidWav {}
+
+/// Finds the `fmt ` and `data` chunks of a WAV file.
+pub fn parse_wav(data: &[u8]) -> Result<Wav<'_>, InvalidWav> {
+    if data.len() < 12 || &data[..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(InvalidWav("missing RIFF/WAVE header"));
+    }
+
+    let mut fmt: Option<&[u8]> = None;
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let size = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]])
+            as usize;
+        let body_start = pos + 8;
+        // Streaming writers may leave the size unset, so clamp to the file
+        let body_end = body_start.saturating_add(size).min(data.len());
+
+        match id {
+            b"fmt " => fmt = Some(&data[body_start..body_end]),
+            b"data" => {
+                let fmt = fmt.ok_or(InvalidWav("data chunk before fmt chunk"))?;
+                if fmt.len() < 16 {
+                    return Err(InvalidWav("fmt chunk too short"));
+                }
+                let sample_rate = u32::from_le_bytes([fmt[4], fmt[5], fmt[6], fmt[7]]);
+                let block_align = u16::from_le_bytes([fmt[12], fmt[13]]) as usize;
+                if sample_rate == 0 || block_align == 0 {
+                    return Err(InvalidWav("zero sample rate or block alignment"));
+                }
+                return Ok(Wav {
+                    fmt,
+                    sample_rate,
+                    block_align,
+                    samples: &data[body_start..body_end],
+                });
+            }
+            _ => {}
+        }
+        pos = body_start.saturating_add(size).saturating_add(size & 1);
+    }
+
+    Err(InvalidWav("missing data chunk"))
+}
+
+/// Wraps samples in a WAV file with the given `fmt ` chunk body.
+pub fn write_wav(fmt: &[u8], samples: &[u8]) -> Vec<u8> {
+    let riff_size = 4 + 8 + fmt.len() + 8 + samples.len();
+    let mut wav = Vec::with_capacity(riff_size + 8);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(riff_size as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+    wav.extend_from_slice(fmt);
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(samples);
+    wav
+}
+
+/// Bytes per sample frame of integer PCM, all channels included.
+pub fn block_align(channels: u16, bits_per_sample: u16) -> u16 {
+    channels * bits_per_sample.div_ceil(8)
+}
+
+/// Body of a WAV `fmt ` chunk for integer PCM.
+pub fn fmt_chunk(sample_rate: u32, channels: u16, bits_per_sample: u16) -> Vec<u8> {
+    let block_align = block_align(channels, bits_per_sample);
+    let byte_rate = sample_rate * block_align as u32;
+    let mut fmt = Vec::with_capacity(16);
+    fmt.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    fmt.extend_from_slice(&channels.to_le_bytes());
+    fmt.extend_from_slice(&sample_rate.to_le_bytes());
+    fmt.extend_from_slice(&byte_rate.to_le_bytes());
+    fmt.extend_from_slice(&block_align.to_le_bytes());
+    fmt.extend_from_slice(&bits_per_sample.to_le_bytes());
+    fmt
+}
+
+/// Size of a leading ID3v2 tag, including its optional footer.
+pub fn id3v2_len(data: &[u8]) -> usize {
+    if data.len() < 10 || !data.starts_with(b"ID3") {
+        return 0;
+    }
+    let size = data[6..10]
+        .iter()
+        .fold(0usize, |size, byte| (size << 7) | (*byte as usize & 0x7F));
+    let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+    (10 + size + footer).min(data.len())
+}
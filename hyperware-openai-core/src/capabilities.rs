@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Route listing the models a server serves.
+pub const MODELS_ROUTE: &str = "models";
+/// Route listing the voices of a speech server. Not part of OpenAI's API, but
+/// offered by several compatible servers.
+pub const VOICES_ROUTE: &str = "audio/voices";
+
+/// What a server reports it supports, used to reject requests it would fail.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServerCapabilities {
+    /// Model ids from the models route. Empty accepts any model.
+    pub models: Vec<String>,
+    /// Voices from the voices route; `None` when the server does not list them.
+    pub voices: Option<Vec<String>>,
+    /// Response formats the server produces, e.g. `["mp3", "wav"]`. Servers
+    /// have no route listing them, so probing leaves this `None`, which
+    /// accepts any format; fill it in from the server's documentation.
+    #[serde(default)]
+    pub formats: Option<Vec<String>>,
+}
+
+impl ServerCapabilities {
+    pub fn supports_model(&self, model: &str) -> bool {
+        self.models.is_empty() || contains(&self.models, model)
+    }
+
+    pub fn supports_voice(&self, voice: &str) -> bool {
+        self.voices
+            .as_ref()
+            .map(|voices| contains(voices, voice))
+            .unwrap_or(true)
+    }
+
+    pub fn supports_format(&self, format: &str) -> bool {
+        self.formats
+            .as_ref()
+            .map(|formats| contains(formats, format))
+            .unwrap_or(true)
+    }
+
+    /// Reads an OpenAI model list, `{"data": [{"id": "whisper-1"}, ...]}`.
+    pub fn parse_models(body: &[u8]) -> Result<Vec<String>, serde_json::Error> {
+        #[derive(Deserialize)]
+        struct ModelList {
+            data: Vec<ModelEntry>,
+        }
+        #[derive(Deserialize)]
+        struct ModelEntry {
+            id: String,
+        }
+
+        let list: ModelList = serde_json::from_slice(body)?;
+        Ok(list.data.into_iter().map(|model| model.id).collect())
+    }
+
+    /// Reads a voice list. Servers differ, so this accepts an array, or an
+    /// object holding one under `voices` or `data`, of names or of objects with
+    /// an `id`, `voice_id` or `name`.
+    pub fn parse_voices(body: &[u8]) -> Option<Vec<String>> {
+        let value: Value = serde_json::from_slice(body).ok()?;
+        let entries = match &value {
+            Value::Array(entries) => entries,
+            Value::Object(object) => object
+                .get("voices")
+                .or_else(|| object.get("data"))?
+                .as_array()?,
+            _ => return None,
+        };
+
+        let voices = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                Value::String(name) => Some(name.clone()),
+                Value::Object(object) => ["id", "voice_id", "name"]
+                    .iter()
+                    .find_map(|key| object.get(*key)?.as_str())
+                    .map(str::to_string),
+                _ => None,
+            })
+            .collect();
+        Some(voices)
+    }
+}
+
+fn contains(names: &[String], name: &str) -> bool {
+    names.iter().any(|candidate| candidate.eq_ignore_ascii_case(name))
+}
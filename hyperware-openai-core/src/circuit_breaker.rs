@@ -0,0 +1,258 @@
+use crate::endpoint::Endpoint;
+use crate::speech::{SpeechError, SpeechErrorKind};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// When a circuit opens and how it recovers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitBreakerPolicy {
+    /// Fraction of recent requests, between 0.0 and 1.0, that must fail for
+    /// the circuit to open.
+    pub failure_rate: f64,
+    /// Number of most recent requests the failure rate is taken over.
+    pub window: usize,
+    /// Requests needed in the window before the circuit can open.
+    pub minimum_requests: usize,
+    /// How long an open circuit rejects requests before letting probes through.
+    pub open_duration: Duration,
+    /// Probes let through at once while half-open. This many must succeed in
+    /// a row to close the circuit.
+    pub half_open_probes: u32,
+    /// Error kinds counted as failures; other errors show the endpoint is up.
+    /// Unavailability (5xx, timeouts, connection failures) by default.
+    pub trip_on: Vec<SpeechErrorKind>,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_rate: 0.5,
+            window: 20,
+            minimum_requests: 10,
+            open_duration: Duration::from_secs(30),
+            half_open_probes: 1,
+            trip_on: vec![SpeechErrorKind::Unavailable],
+        }
+    }
+}
+
+impl CircuitBreakerPolicy {
+    pub fn is_failure(&self, error: &SpeechError) -> bool {
+        self.trip_on.contains(&error.kind)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent.
+    Closed,
+    /// Requests fail without being sent.
+    Open,
+    /// Probe requests are sent to find out whether the endpoint recovered.
+    HalfOpen,
+}
+
+/// The circuit of one endpoint, for monitoring.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitStatus {
+    pub endpoint: Endpoint,
+    pub state: CircuitState,
+    /// Requests in the failure-rate window, and how many of them failed.
+    pub requests: usize,
+    pub failures: usize,
+    /// Time left until an open circuit lets probes through; zero otherwise.
+    pub retry_after: Duration,
+}
+
+#[derive(Debug)]
+struct Circuit {
+    state: CircuitState,
+    /// Recent outcomes, `true` for a failure.
+    outcomes: VecDeque<bool>,
+    /// When the circuit opened or, while half-open, when the last probe went out.
+    since: Instant,
+    probes: u32,
+    probe_successes: u32,
+}
+
+impl Circuit {
+    fn new(now: Instant) -> Self {
+        Self {
+            state: CircuitState::Closed,
+            outcomes: VecDeque::new(),
+            since: now,
+            probes: 0,
+            probe_successes: 0,
+        }
+    }
+
+    fn failures(&self) -> usize {
+        self.outcomes.iter().filter(|failed| **failed).count()
+    }
+
+    fn open(&mut self, now: Instant) {
+        self.state = CircuitState::Open;
+        self.outcomes.clear();
+        self.since = now;
+        self.probes = 0;
+        self.probe_successes = 0;
+    }
+
+    fn admit_probe(&mut self, now: Instant) {
+        self.state = CircuitState::HalfOpen;
+        self.since = now;
+        self.probes += 1;
+    }
+}
+
+/// Stops sending requests to an endpoint that keeps failing.
+///
+/// Each endpoint has its own circuit. It opens once `failure_rate` of the
+/// recent requests failed, after which requests fail at once for
+/// `open_duration`. Then it half-opens: a few probe requests go out, and the
+/// circuit closes when they succeed or opens again when one fails. A probe
+/// that never reports back is replaced after another `open_duration`.
+///
+/// Share one breaker between the clients of a process through an `Arc`.
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    policy: CircuitBreakerPolicy,
+    circuits: Mutex<HashMap<Endpoint, Circuit>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(policy: CircuitBreakerPolicy) -> Self {
+        Self {
+            policy,
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn policy(&self) -> &CircuitBreakerPolicy {
+        &self.policy
+    }
+
+    /// Lets a request to `endpoint` through, or returns how long until the
+    /// circuit will.
+    pub fn try_acquire(&self, endpoint: &Endpoint) -> Result<(), Duration> {
+        self.try_acquire_at(endpoint, Instant::now())
+    }
+
+    pub fn try_acquire_at(&self, endpoint: &Endpoint, now: Instant) -> Result<(), Duration> {
+        let mut circuits = self.circuits.lock().unwrap();
+        let Some(circuit) = circuits.get_mut(endpoint) else {
+            return Ok(());
+        };
+
+        let reopens = circuit.since + self.policy.open_duration;
+        match circuit.state {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open | CircuitState::HalfOpen if now >= reopens => {
+                // The first probe, or a replacement for probes that never reported back
+                circuit.probes = 0;
+                circuit.admit_probe(now);
+                Ok(())
+            }
+            CircuitState::HalfOpen if circuit.probes < self.policy.half_open_probes.max(1) => {
+                circuit.admit_probe(now);
+                Ok(())
+            }
+            CircuitState::Open | CircuitState::HalfOpen => Err(reopens - now),
+        }
+    }
+
+    /// Records the outcome of a request let through by
+    /// [`try_acquire`](Self::try_acquire): `None` for success.
+    pub fn record(&self, endpoint: &Endpoint, error: Option<&SpeechError>) {
+        self.record_at(endpoint, error, Instant::now());
+    }
+
+    pub fn record_at(&self, endpoint: &Endpoint, error: Option<&SpeechError>, now: Instant) {
+        let failed = error.is_some_and(|error| self.policy.is_failure(error));
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits
+            .entry(endpoint.clone())
+            .or_insert_with(|| Circuit::new(now));
+
+        match circuit.state {
+            CircuitState::Closed => {
+                circuit.outcomes.push_back(failed);
+                while circuit.outcomes.len() > self.policy.window.max(1) {
+                    circuit.outcomes.pop_front();
+                }
+                let requests = circuit.outcomes.len();
+                let failures = circuit.failures();
+                if failures > 0
+                    && requests >= self.policy.minimum_requests
+                    && failures as f64 >= self.policy.failure_rate * requests as f64
+                {
+                    circuit.open(now);
+                }
+            }
+            CircuitState::HalfOpen if failed => circuit.open(now),
+            CircuitState::HalfOpen => {
+                circuit.probes = circuit.probes.saturating_sub(1);
+                circuit.probe_successes += 1;
+                if circuit.probe_successes >= self.policy.half_open_probes {
+                    *circuit = Circuit::new(now);
+                }
+            }
+            // A request sent before the circuit opened
+            CircuitState::Open => {}
+        }
+    }
+
+    pub fn state(&self, endpoint: &Endpoint) -> CircuitState {
+        self.status(endpoint).state
+    }
+
+    pub fn status(&self, endpoint: &Endpoint) -> CircuitStatus {
+        self.status_at(endpoint, Instant::now())
+    }
+
+    pub fn status_at(&self, endpoint: &Endpoint, now: Instant) -> CircuitStatus {
+        let circuits = self.circuits.lock().unwrap();
+        match circuits.get(endpoint) {
+            Some(circuit) => self.circuit_status(endpoint, circuit, now),
+            None => CircuitStatus {
+                endpoint: endpoint.clone(),
+                state: CircuitState::Closed,
+                requests: 0,
+                failures: 0,
+                retry_after: Duration::ZERO,
+            },
+        }
+    }
+
+    /// The circuits of every endpoint a request has been recorded for.
+    pub fn statuses(&self) -> Vec<CircuitStatus> {
+        let now = Instant::now();
+        let circuits = self.circuits.lock().unwrap();
+        circuits
+            .iter()
+            .map(|(endpoint, circuit)| self.circuit_status(endpoint, circuit, now))
+            .collect()
+    }
+
+    /// Closes the circuit of `endpoint` and forgets its history.
+    pub fn reset(&self, endpoint: &Endpoint) {
+        self.circuits.lock().unwrap().remove(endpoint);
+    }
+
+    fn circuit_status(&self, endpoint: &Endpoint, circuit: &Circuit, now: Instant) -> CircuitStatus {
+        let retry_after = match circuit.state {
+            CircuitState::Open => {
+                (circuit.since + self.policy.open_duration).saturating_duration_since(now)
+            }
+            _ => Duration::ZERO,
+        };
+        CircuitStatus {
+            endpoint: endpoint.clone(),
+            state: circuit.state,
+            requests: circuit.outcomes.len(),
+            failures: circuit.failures(),
+            retry_after,
+        }
+    }
+}
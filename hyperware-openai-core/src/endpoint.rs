@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fmt;
+use url::Url;
+
+pub const OPENAI_BASE_URL: &str = "https://api.openai.com";
+
+/// Where the audio routes live.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// OpenAI's API: `{base_url}/v1/audio/...`.
+    OpenAi { base_url: String },
+    /// An Azure OpenAI deployment:
+    /// `{resource_url}/openai/deployments/{deployment}/audio/...?api-version=...`.
+    /// The deployment decides the model; the `model` field is ignored by Azure.
+    Azure {
+        resource_url: String,
+        deployment: String,
+        api_version: String,
+    },
+    /// A server implementing OpenAI's routes under `path_prefix`, `/v1` by
+    /// default: `{base_url}{path_prefix}/audio/...`. Any model, voice and
+    /// format names are passed through; OpenAI's model rules are not applied.
+    Compatible {
+        base_url: String,
+        path_prefix: String,
+    },
+}
+
+impl Endpoint {
+    /// URL of an audio route such as `audio/speech`.
+    pub fn url(&self, route: &str) -> Result<Url, url::ParseError> {
+        let route = route.trim_start_matches('/');
+        match self {
+            Endpoint::OpenAi { base_url } => {
+                Url::parse(&format!("{}/v1/{}", base_url.trim_end_matches('/'), route))
+            }
+            Endpoint::Compatible {
+                base_url,
+                path_prefix,
+            } => {
+                let mut url = base_url.trim_end_matches('/').to_string();
+                let path_prefix = path_prefix.trim_matches('/');
+                if !path_prefix.is_empty() {
+                    url.push('/');
+                    url.push_str(path_prefix);
+                }
+                Url::parse(&format!("{}/{}", url, route))
+            }
+            Endpoint::Azure {
+                resource_url,
+                deployment,
+                api_version,
+            } => {
+                let mut url = Url::parse(&format!(
+                    "{}/openai/deployments",
+                    resource_url.trim_end_matches('/')
+                ))?;
+                // Deployment names are user-chosen, so encode them as one segment
+                url.path_segments_mut()
+                    .map_err(|_| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+                    .push(deployment)
+                    .extend(route.split('/'));
+                url.query_pairs_mut().append_pair("api-version", api_version);
+                Ok(url)
+            }
+        }
+    }
+
+    /// Replaces the host part of the endpoint, keeping the deployment of an
+    /// Azure endpoint.
+    pub fn set_base_url(&mut self, url: impl Into<String>) {
+        match self {
+            Endpoint::OpenAi { base_url } | Endpoint::Compatible { base_url, .. } => {
+                *base_url = url.into()
+            }
+            Endpoint::Azure { resource_url, .. } => *resource_url = url.into(),
+        }
+    }
+
+    /// Whether requests are checked against OpenAI's models, e.g. that only
+    /// `whisper-1` translates. False for compatible servers and for Azure,
+    /// where the deployment rather than the model name picks the model.
+    pub fn enforces_openai_models(&self) -> bool {
+        matches!(self, Endpoint::OpenAi { .. })
+    }
+}
+
+/// Shows where the routes live, e.g. `https://api.openai.com/v1`.
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endpoint::OpenAi { base_url } => write!(f, "{}/v1", base_url.trim_end_matches('/')),
+            Endpoint::Azure {
+                resource_url,
+                deployment,
+                ..
+            } => write!(
+                f,
+                "{}/openai/deployments/{}",
+                resource_url.trim_end_matches('/'),
+                deployment
+            ),
+            Endpoint::Compatible {
+                base_url,
+                path_prefix,
+            } => {
+                write!(f, "{}", base_url.trim_end_matches('/'))?;
+                match path_prefix.trim_matches('/') {
+                    "" => Ok(()),
+                    prefix => write!(f, "/{}", prefix),
+                }
+            }
+        }
+    }
+}
+
+/// How requests are authenticated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Auth {
+    /// `Authorization: Bearer ...`: an OpenAI API key or an Azure AD (Entra ID) token.
+    Bearer(String),
+    /// `api-key: ...`, Azure's key authentication.
+    ApiKey(String),
+    /// No credentials, for servers that do not check them.
+    None,
+}
+
+impl Auth {
+    /// Whether the credential is empty.
+    pub fn is_missing(&self) -> bool {
+        match self {
+            Auth::Bearer(secret) | Auth::ApiKey(secret) => secret.is_empty(),
+            Auth::None => false,
+        }
+    }
+
+    pub fn apply_to(&self, headers: &mut HashMap<String, String>) {
+        match self {
+            Auth::Bearer(token) => {
+                headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+            }
+            Auth::ApiKey(key) => {
+                headers.insert("api-key".to_string(), key.clone());
+            }
+            Auth::None => {}
+        }
+    }
+}
+
+/// An endpoint and the credentials it expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiProfile {
+    pub endpoint: Endpoint,
+    pub auth: Auth,
+}
+
+impl ApiProfile {
+    pub fn new(endpoint: Endpoint, auth: Auth) -> Self {
+        Self { endpoint, auth }
+    }
+
+    /// OpenAI's API with a bearer API key.
+    pub fn openai(api_key: impl Into<String>) -> Self {
+        Self::new(
+            Endpoint::OpenAi {
+                base_url: OPENAI_BASE_URL.to_string(),
+            },
+            Auth::Bearer(api_key.into()),
+        )
+    }
+
+    /// An Azure OpenAI deployment authenticated with an `api-key`, e.g.
+    /// `azure_api_key("https://my-resource.openai.azure.com", "whisper", "2024-06-01", key)`.
+    pub fn azure_api_key(
+        resource_url: impl Into<String>,
+        deployment: impl Into<String>,
+        api_version: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            azure_endpoint(resource_url, deployment, api_version),
+            Auth::ApiKey(api_key.into()),
+        )
+    }
+
+    /// An Azure OpenAI deployment authenticated with an Azure AD (Entra ID) token.
+    pub fn azure_ad_token(
+        resource_url: impl Into<String>,
+        deployment: impl Into<String>,
+        api_version: impl Into<String>,
+        token: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            azure_endpoint(resource_url, deployment, api_version),
+            Auth::Bearer(token.into()),
+        )
+    }
+
+    /// An OpenAI-compatible server with a bearer API key.
+    pub fn compatible(base_url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self::new(
+            Endpoint::Compatible {
+                base_url: base_url.into(),
+                path_prefix: "/v1".to_string(),
+            },
+            Auth::Bearer(api_key.into()),
+        )
+    }
+
+    /// An OpenAI-compatible server that needs no API key, such as a local
+    /// whisper or TTS server.
+    pub fn keyless(base_url: impl Into<String>) -> Self {
+        Self {
+            auth: Auth::None,
+            ..Self::compatible(base_url, "")
+        }
+    }
+
+    /// Serves the routes of a compatible endpoint under `path_prefix`
+    /// instead of `/v1`, e.g. `/api/v1` or `""`. Other endpoints are unchanged.
+    pub fn with_path_prefix(mut self, path_prefix: impl Into<String>) -> Self {
+        if let Endpoint::Compatible {
+            path_prefix: prefix,
+            ..
+        } = &mut self.endpoint
+        {
+            *prefix = path_prefix.into();
+        }
+        self
+    }
+}
+
+fn azure_endpoint(
+    resource_url: impl Into<String>,
+    deployment: impl Into<String>,
+    api_version: impl Into<String>,
+) -> Endpoint {
+    Endpoint::Azure {
+        resource_url: resource_url.into(),
+        deployment: deployment.into(),
+        api_version: api_version.into(),
+    }
+}
@@ -0,0 +1,80 @@
+use crate::endpoint::{ApiProfile, Endpoint};
+use crate::speech::{SpeechError, SpeechErrorKind};
+
+/// A model, and optionally another endpoint, to send a failed request to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FallbackTarget<M> {
+    /// Endpoint and credentials; the client's own when `None`.
+    pub profile: Option<ApiProfile>,
+    pub model: M,
+}
+
+impl<M> FallbackTarget<M> {
+    /// `model` on the client's endpoint.
+    pub fn model(model: M) -> Self {
+        Self {
+            profile: None,
+            model,
+        }
+    }
+
+    /// `model` on the endpoint of `profile`.
+    pub fn new(profile: ApiProfile, model: M) -> Self {
+        Self {
+            profile: Some(profile),
+            model,
+        }
+    }
+}
+
+/// Targets to try, in order, when a request fails.
+///
+/// The request is first sent as built. While it fails with an error of a kind
+/// in `fall_back_on`, it is sent to the next target, with parameters the
+/// target's model does not support adjusted or dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FallbackPolicy<M> {
+    pub targets: Vec<FallbackTarget<M>>,
+    /// Rate limiting and unavailability (5xx, timeouts, connection failures)
+    /// by default.
+    pub fall_back_on: Vec<SpeechErrorKind>,
+}
+
+impl<M> FallbackPolicy<M> {
+    pub fn new(targets: Vec<FallbackTarget<M>>) -> Self {
+        Self {
+            targets,
+            fall_back_on: vec![SpeechErrorKind::RateLimited, SpeechErrorKind::Unavailable],
+        }
+    }
+
+    /// Falls back to each model in turn on the client's endpoint.
+    pub fn models(models: impl IntoIterator<Item = M>) -> Self {
+        Self::new(models.into_iter().map(FallbackTarget::model).collect())
+    }
+
+    pub fn with_fall_back_on(mut self, kinds: Vec<SpeechErrorKind>) -> Self {
+        self.fall_back_on = kinds;
+        self
+    }
+
+    pub fn falls_back_on(&self, error: &SpeechError) -> bool {
+        self.fall_back_on.contains(&error.kind)
+    }
+}
+
+/// The target that produced a response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServedBy {
+    /// 0 for the request as built, `n` for the `n`th fallback target.
+    pub index: usize,
+    pub model: String,
+    pub endpoint: Endpoint,
+}
+
+/// A response and the target that served it.
+#[derive(Debug, Clone)]
+pub struct FallbackResponse<T> {
+    pub response: T,
+    pub served_by: ServedBy,
+}
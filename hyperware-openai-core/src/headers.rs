@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+/// Header selecting the organization a request is billed to.
+pub const ORGANIZATION_HEADER: &str = "OpenAI-Organization";
+/// Header selecting the project a request is billed to.
+pub const PROJECT_HEADER: &str = "OpenAI-Project";
+
+/// Extra HTTP headers sent with requests.
+///
+/// Names are compared case-insensitively, so inserting a header again
+/// replaces it, and applying the set replaces headers the client built itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CustomHeaders {
+    entries: Vec<(String, String)>,
+}
+
+impl CustomHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        self.entries
+            .retain(|(existing, _)| !existing.eq_ignore_ascii_case(&name));
+        self.entries.push((name, value.into()));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes these headers into `headers`, replacing any of the same name.
+    pub fn apply_to(&self, headers: &mut HashMap<String, String>) {
+        for (name, value) in &self.entries {
+            headers.retain(|existing, _| !existing.eq_ignore_ascii_case(name));
+            headers.insert(name.clone(), value.clone());
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Pieces shared by the hyperware-openai-stt and hyperware-openai-tts clients.
+
+pub mod api_error;
+pub mod audio;
+pub mod capabilities;
+pub mod circuit_breaker;
+pub mod endpoint;
+pub mod fallback;
+pub mod headers;
+pub mod metadata;
+pub mod rate_limit;
+pub mod retry;
+pub mod speech;
+pub mod sse;
+pub mod transport;
+mod wire;
+
+#[cfg(test)]
+mod tests;
+
+#[doc(hidden)]
+pub use serde as __serde;
+
+pub use api_error::{ApiErrorDetails, ApiErrorKind};
+pub use audio::{InvalidWav, Wav};
+pub use capabilities::ServerCapabilities;
+pub use circuit_breaker::{CircuitBreaker, CircuitBreakerPolicy, CircuitState, CircuitStatus};
+pub use endpoint::{ApiProfile, Auth, Endpoint};
+pub use fallback::{FallbackPolicy, FallbackResponse, FallbackTarget, ServedBy};
+pub use headers::{CustomHeaders, ORGANIZATION_HEADER, PROJECT_HEADER};
+pub use metadata::{RateLimitInfo, ResponseMetadata};
+pub use rate_limit::{RateLimiter, Throttle, TokenBucket};
+pub use retry::{send_streaming_with_retry, send_with_retry, RetryPolicy};
+pub use speech::{
+    SpeechError, SpeechErrorKind, SpeechFuture, SpeechToText, SpeechToTextRequest,
+    SynthesizedSpeech, TextToSpeech, TextToSpeechRequest, TimedText, TimedTranscript,
+};
+pub use sse::{parse_events, SseEvent, SseParser};
+pub use transport::{
+    BodySink, HttpRequest, HttpTransport, HyperwareTransport, InMemoryTransport, SleepFuture,
+    TransportFuture,
+};
@@ -0,0 +1,72 @@
+use crate::retry::parse_reset_duration;
+use http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Information the API returns in the headers of every response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseMetadata {
+    /// The `x-request-id` header, needed when contacting OpenAI support.
+    pub request_id: Option<String>,
+    /// Server-side processing time from `openai-processing-ms`.
+    pub processing_ms: Option<u64>,
+    /// The model that served the request, from `openai-model`.
+    pub model: Option<String>,
+    pub rate_limit: RateLimitInfo,
+}
+
+/// The `x-ratelimit-*` headers of a response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitInfo {
+    pub limit_requests: Option<u64>,
+    pub limit_tokens: Option<u64>,
+    pub remaining_requests: Option<u64>,
+    pub remaining_tokens: Option<u64>,
+    /// Time until the request limit is fully replenished.
+    pub reset_requests: Option<Duration>,
+    /// Time until the token limit is fully replenished.
+    pub reset_tokens: Option<Duration>,
+}
+
+impl ResponseMetadata {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            request_id: header(headers, "x-request-id").map(|value| value.to_string()),
+            processing_ms: header(headers, "openai-processing-ms")
+                .and_then(|value| value.parse::<f64>().ok())
+                .filter(|ms| ms.is_finite() && *ms >= 0.0)
+                .map(|ms| ms.round() as u64),
+            model: header(headers, "openai-model").map(|value| value.to_string()),
+            rate_limit: RateLimitInfo::from_headers(headers),
+        }
+    }
+}
+
+impl RateLimitInfo {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let number = |name: &str| header(headers, name).and_then(|value| value.parse().ok());
+        let duration = |name: &str| header(headers, name).and_then(parse_reset_duration);
+
+        Self {
+            limit_requests: number("x-ratelimit-limit-requests"),
+            limit_tokens: number("x-ratelimit-limit-tokens"),
+            remaining_requests: number("x-ratelimit-remaining-requests"),
+            remaining_tokens: number("x-ratelimit-remaining-tokens"),
+            reset_requests: duration("x-ratelimit-reset-requests"),
+            reset_tokens: duration("x-ratelimit-reset-tokens"),
+        }
+    }
+
+    /// Whether the response carried any rate-limit headers.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
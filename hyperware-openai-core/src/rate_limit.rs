@@ -0,0 +1,192 @@
+use crate::metadata::RateLimitInfo;
+use crate::transport::HttpTransport;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A token bucket refilled continuously at `capacity` tokens per minute.
+///
+/// Reservations may take the balance below zero; the deficit is how long the
+/// caller has to wait, so concurrent callers queue up instead of racing.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket holding `capacity` tokens, refilled over one minute.
+    pub fn per_minute(capacity: f64) -> Self {
+        Self::per_minute_at(capacity, Instant::now())
+    }
+
+    pub fn per_minute_at(capacity: f64, now: Instant) -> Self {
+        let capacity = capacity.max(0.0);
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: capacity / 60.0,
+            updated: now,
+        }
+    }
+
+    pub fn capacity(&self) -> f64 {
+        self.capacity
+    }
+
+    /// Tokens available at `now`; negative while earlier reservations are pending.
+    pub fn available_at(&mut self, now: Instant) -> f64 {
+        self.refill(now);
+        self.tokens
+    }
+
+    /// Takes `cost` tokens and returns how long to wait before using them.
+    pub fn reserve_at(&mut self, cost: f64, now: Instant) -> Duration {
+        self.refill(now);
+        if self.refill_per_sec <= 0.0 {
+            return Duration::ZERO;
+        }
+        // A request larger than the bucket waits for a full bucket, not forever
+        self.tokens -= cost.max(0.0).min(self.capacity);
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.refill_per_sec)
+        }
+    }
+
+    /// Takes `cost` tokens without waiting, or returns them when negative.
+    pub fn charge_at(&mut self, cost: f64, now: Instant) {
+        self.refill(now);
+        self.tokens = (self.tokens - cost.min(self.capacity)).min(self.capacity);
+    }
+
+    /// Lowers the balance to what the server reports as remaining. When nothing
+    /// remains, the next token is held back until `reset` has passed.
+    pub fn observe_at(&mut self, remaining: u64, reset: Option<Duration>, now: Instant) {
+        self.refill(now);
+        self.tokens = self.tokens.min(remaining as f64);
+        if remaining == 0 {
+            if let Some(reset) = reset {
+                self.tokens = self.tokens.min(1.0 - reset.as_secs_f64() * self.refill_per_sec);
+            }
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.updated = self.updated.max(now);
+    }
+}
+
+/// What each attempt of a request takes from a [`RateLimiter`], for
+/// [`send_with_retry`](crate::retry::send_with_retry).
+#[derive(Debug, Clone, Copy)]
+pub struct Throttle<'a> {
+    pub limiter: &'a RateLimiter,
+    /// Seconds of audio uploaded with every attempt.
+    pub audio_seconds: f64,
+}
+
+impl<'a> Throttle<'a> {
+    pub fn new(limiter: &'a RateLimiter, audio_seconds: f64) -> Self {
+        Self {
+            limiter,
+            audio_seconds,
+        }
+    }
+}
+
+/// Client-side throttle shared by the requests of one or more clients.
+///
+/// Requests are limited per minute and, for transcription and translation,
+/// by seconds of uploaded audio per minute. The request budget follows the
+/// `x-ratelimit-*-requests` headers of every response.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    requests: Option<Mutex<TokenBucket>>,
+    audio_seconds: Option<Mutex<TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_requests_per_minute(mut self, requests: u32) -> Self {
+        self.requests = Some(Mutex::new(TokenBucket::per_minute(requests as f64)));
+        self
+    }
+
+    /// Limits the seconds of audio sent for transcription per minute.
+    pub fn with_audio_seconds_per_minute(mut self, seconds: f64) -> Self {
+        self.audio_seconds = Some(Mutex::new(TokenBucket::per_minute(seconds)));
+        self
+    }
+
+    /// Whether uploads are limited by seconds of audio. Callers only need to
+    /// measure the audio they send when this is set.
+    pub fn limits_audio(&self) -> bool {
+        self.audio_seconds.is_some()
+    }
+
+    /// Reserves capacity for one request carrying `audio_seconds` of audio and
+    /// returns how long to wait before sending it.
+    pub fn reserve(&self, audio_seconds: f64) -> Duration {
+        self.reserve_at(audio_seconds, Instant::now())
+    }
+
+    pub fn reserve_at(&self, audio_seconds: f64, now: Instant) -> Duration {
+        let request_wait = self
+            .requests
+            .as_ref()
+            .map(|bucket| bucket.lock().unwrap().reserve_at(1.0, now))
+            .unwrap_or_default();
+        let audio_wait = self
+            .audio_seconds
+            .as_ref()
+            .filter(|_| audio_seconds > 0.0)
+            .map(|bucket| bucket.lock().unwrap().reserve_at(audio_seconds, now))
+            .unwrap_or_default();
+        request_wait.max(audio_wait)
+    }
+
+    /// Corrects the audio budget by `audio_seconds` without waiting, for audio
+    /// whose length was only known once the request had been sent: positive
+    /// when more was sent than reserved, negative to return an overestimate.
+    /// Later requests wait for any deficit.
+    pub fn charge_audio(&self, audio_seconds: f64) {
+        self.charge_audio_at(audio_seconds, Instant::now());
+    }
+
+    pub fn charge_audio_at(&self, audio_seconds: f64, now: Instant) {
+        if let Some(bucket) = &self.audio_seconds {
+            bucket.lock().unwrap().charge_at(audio_seconds, now);
+        }
+    }
+
+    /// Waits, through the transport's timer, until a request carrying
+    /// `audio_seconds` of audio may be sent.
+    pub async fn acquire(&self, transport: &dyn HttpTransport, audio_seconds: f64) {
+        let wait = self.reserve(audio_seconds);
+        if !wait.is_zero() {
+            transport.sleep(wait).await;
+        }
+    }
+
+    /// Applies the rate-limit headers of a response.
+    pub fn observe(&self, info: &RateLimitInfo) {
+        self.observe_at(info, Instant::now());
+    }
+
+    pub fn observe_at(&self, info: &RateLimitInfo, now: Instant) {
+        if let (Some(bucket), Some(remaining)) = (&self.requests, info.remaining_requests) {
+            bucket
+                .lock()
+                .unwrap()
+                .observe_at(remaining, info.reset_requests, now);
+        }
+    }
+}
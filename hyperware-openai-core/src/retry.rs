@@ -0,0 +1,268 @@
+use crate::api_error::{ApiErrorDetails, ApiErrorKind};
+use crate::metadata::RateLimitInfo;
+use crate::rate_limit::Throttle;
+use crate::transport::{BodySink, HttpRequest, HttpTransport};
+use http::HeaderMap;
+use hyperware_process_lib::http::client::HttpClientError;
+use rand::Rng;
+use std::time::Duration;
+
+/// When and how often a failed request is retried.
+///
+/// Delays grow exponentially from `base_delay` up to `max_delay`, unless the
+/// server says how long to wait through `Retry-After` or the
+/// `x-ratelimit-reset-*` headers.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one; `1` disables retries.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Fraction of each backoff delay, between 0.0 and 1.0, that is randomized.
+    pub jitter: f64,
+    /// HTTP statuses that are retried. A 429 for exhausted quota
+    /// (`insufficient_quota`) is never retried.
+    pub retry_statuses: Vec<u16>,
+    /// Decides which transport failures are retried.
+    pub retry_client_error: fn(&HttpClientError) -> bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.2,
+            retry_statuses: vec![408, 409, 429, 500, 502, 503, 504],
+            retry_client_error: is_transient_client_error,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that sends every request exactly once.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn should_retry_status(&self, status: u16) -> bool {
+        self.retry_statuses.contains(&status)
+    }
+
+    /// Whether `response` is retried: its status is, and it is not a 429
+    /// saying the quota is used up, which waiting does not fix.
+    pub fn should_retry_response(&self, response: &http::Response<Vec<u8>>) -> bool {
+        let status = response.status().as_u16();
+        if !self.should_retry_status(status) {
+            return false;
+        }
+        status != 429
+            || ApiErrorDetails::from_response(status, response.headers(), response.body()).kind()
+                != ApiErrorKind::QuotaExceeded
+    }
+
+    /// Delay before retry number `attempt` (1 for the first retry).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
+        delay.mul_f64(1.0 - jitter * rand::thread_rng().gen::<f64>())
+    }
+
+    /// Delay before retry number `attempt`, preferring what the server asked for.
+    pub fn delay_for(&self, attempt: u32, headers: Option<&HeaderMap>) -> Duration {
+        headers
+            .and_then(server_retry_delay)
+            .map(|delay| delay.min(self.max_delay))
+            .unwrap_or_else(|| self.backoff(attempt))
+    }
+}
+
+/// Connection and execution failures are worth retrying; malformed requests are not.
+pub fn is_transient_client_error(error: &HttpClientError) -> bool {
+    matches!(
+        error,
+        HttpClientError::ExecuteRequestFailed(_) | HttpClientError::WsOpenFailed { .. }
+    )
+}
+
+/// How long the server asked the client to wait, from `retry-after-ms`,
+/// `Retry-After` (in seconds) or, for exhausted limits, `x-ratelimit-reset-*`.
+/// Values that are negative or too large for a `Duration` are ignored.
+pub fn server_retry_delay(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let seconds = |name: &str, scale: f64| {
+        let value = header(name)?.trim().parse::<f64>().ok()?;
+        Duration::try_from_secs_f64(value / scale).ok()
+    };
+
+    if let Some(delay) = seconds("retry-after-ms", 1000.0) {
+        return Some(delay);
+    }
+    if let Some(delay) = seconds("retry-after", 1.0) {
+        return Some(delay);
+    }
+
+    ["requests", "tokens"]
+        .iter()
+        .filter(|kind| {
+            header(&format!("x-ratelimit-remaining-{}", kind))
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                == Some(0)
+        })
+        .filter_map(|kind| header(&format!("x-ratelimit-reset-{}", kind)))
+        .filter_map(parse_reset_duration)
+        .max()
+}
+
+/// Parses the duration format of the `x-ratelimit-reset-*` headers, such as
+/// `1s`, `6m0s`, `20ms` or `1h2m3.5s`. Durations too large for a `Duration`
+/// are `None`.
+pub fn parse_reset_duration(value: &str) -> Option<Duration> {
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut total = 0.0;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+
+        let (unit_secs, unit_len) = if rest.starts_with("ms") {
+            (0.001, 2)
+        } else if rest.starts_with("us") {
+            (0.000_001, 2)
+        } else if rest.starts_with("µs") {
+            (0.000_001, "µs".len())
+        } else if rest.starts_with("ns") {
+            (0.000_000_001, 2)
+        } else if rest.starts_with('h') {
+            (3600.0, 1)
+        } else if rest.starts_with('m') {
+            (60.0, 1)
+        } else if rest.starts_with('s') {
+            (1.0, 1)
+        } else if rest.is_empty() {
+            // A bare number is taken as seconds
+            (1.0, 0)
+        } else {
+            return None;
+        };
+        total += number * unit_secs;
+        rest = &rest[unit_len..];
+    }
+
+    Duration::try_from_secs_f64(total).ok()
+}
+
+/// Waits for `throttle` before an attempt.
+async fn acquire(transport: &dyn HttpTransport, throttle: Option<Throttle<'_>>) {
+    if let Some(throttle) = throttle {
+        throttle.limiter.acquire(transport, throttle.audio_seconds).await;
+    }
+}
+
+/// Feeds the rate-limit headers of an attempt's response to `throttle`.
+fn observe(
+    throttle: Option<Throttle<'_>>,
+    result: &Result<http::Response<Vec<u8>>, HttpClientError>,
+) {
+    if let (Some(throttle), Ok(response)) = (throttle, result) {
+        throttle.limiter.observe(&RateLimitInfo::from_headers(response.headers()));
+    }
+}
+
+/// Sends `request`, retrying retryable statuses and transport failures
+/// according to `policy` and sleeping through the transport between attempts.
+/// Every attempt, retries included, first waits for and takes its share of
+/// `throttle`.
+///
+/// The last response is returned as-is, even if its status is an error.
+pub async fn send_with_retry(
+    transport: &dyn HttpTransport,
+    policy: &RetryPolicy,
+    throttle: Option<Throttle<'_>>,
+    request: HttpRequest,
+) -> Result<http::Response<Vec<u8>>, HttpClientError> {
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        acquire(transport, throttle).await;
+        if attempt >= max_attempts {
+            let result = transport.send(request).await;
+            observe(throttle, &result);
+            return result;
+        }
+
+        let result = transport.send(request.clone()).await;
+        observe(throttle, &result);
+        let delay = match result {
+            Ok(response) if policy.should_retry_response(&response) => {
+                policy.delay_for(attempt, Some(response.headers()))
+            }
+            Err(error) if (policy.retry_client_error)(&error) => policy.delay_for(attempt, None),
+            result => return result,
+        };
+
+        transport.sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Like [`send_with_retry`], but hands the body of a successful response to
+/// `on_chunk` as it arrives; see [`HttpTransport::send_streaming`].
+///
+/// Once part of the body has been delivered, a failure is returned rather
+/// than retried, so no chunk reaches `on_chunk` twice.
+pub async fn send_streaming_with_retry(
+    transport: &dyn HttpTransport,
+    policy: &RetryPolicy,
+    throttle: Option<Throttle<'_>>,
+    request: HttpRequest,
+    on_chunk: &mut BodySink<'_>,
+) -> Result<http::Response<Vec<u8>>, HttpClientError> {
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    let mut delivered = false;
+    loop {
+        acquire(transport, throttle).await;
+        let mut sink = |headers: &HeaderMap, chunk: &[u8]| {
+            delivered = true;
+            on_chunk(headers, chunk);
+        };
+        if attempt >= max_attempts {
+            let result = transport.send_streaming(request, &mut sink).await;
+            observe(throttle, &result);
+            return result;
+        }
+
+        let result = transport.send_streaming(request.clone(), &mut sink).await;
+        observe(throttle, &result);
+        let delay = match result {
+            Ok(response) if policy.should_retry_response(&response) => {
+                policy.delay_for(attempt, Some(response.headers()))
+            }
+            Err(error) if !delivered && (policy.retry_client_error)(&error) => {
+                policy.delay_for(attempt, None)
+            }
+            result => return result,
+        };
+
+        transport.sleep(delay).await;
+        attempt += 1;
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+pub type SpeechFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, SpeechError>> + 'a>>;
+
+/// A backend that turns audio into a timed transcript.
+///
+/// Implemented by `TranscriptionClient`; application code written against the
+/// trait can use any other backend or a local stand-in instead.
+pub trait SpeechToText {
+    fn speech_to_text(&self, request: SpeechToTextRequest) -> SpeechFuture<'_, TimedTranscript>;
+}
+
+/// A backend that turns text into audio. Implemented by `SpeechClient`.
+pub trait TextToSpeech {
+    fn text_to_speech(&self, request: TextToSpeechRequest) -> SpeechFuture<'_, SynthesizedSpeech>;
+}
+
+/// Audio to transcribe.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpeechToTextRequest {
+    pub audio: Vec<u8>,
+    /// Name of the audio file; its extension hints at the container.
+    pub file_name: String,
+    /// Backend-specific model id; the backend's default when `None`.
+    pub model: Option<String>,
+    /// ISO-639-1 language of the audio, if known.
+    pub language: Option<String>,
+    /// Text to guide spelling and style.
+    pub prompt: Option<String>,
+}
+
+/// Transcribed text with timings, as far as the backend provides them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimedTranscript {
+    pub text: String,
+    /// Detected or requested language.
+    pub language: Option<String>,
+    /// Duration of the audio in seconds.
+    pub duration: Option<f64>,
+    /// Empty when the backend does not report timings.
+    pub segments: Vec<TimedText>,
+    pub words: Vec<TimedText>,
+}
+
+/// Text spoken between `start` and `end`, in seconds from the start of the audio.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedText {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// Text to speak.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextToSpeechRequest {
+    pub text: String,
+    /// Backend-specific voice name; the backend's default when `None`.
+    pub voice: Option<String>,
+    /// Backend-specific model id; the backend's default when `None`.
+    pub model: Option<String>,
+    /// Audio format such as `mp3` or `wav`; the backend's default when `None`.
+    pub format: Option<String>,
+    pub speed: Option<f32>,
+    /// How to speak, for backends that take style instructions.
+    pub instructions: Option<String>,
+}
+
+/// Audio produced by a [`TextToSpeech`] backend.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SynthesizedSpeech {
+    pub audio: Vec<u8>,
+    /// Format of `audio`, e.g. `mp3`.
+    pub format: String,
+}
+
+/// A failure of a speech backend, reduced to what callers can act on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeechError {
+    pub kind: SpeechErrorKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpeechErrorKind {
+    /// The request was rejected and will fail again unchanged.
+    InvalidRequest,
+    /// Credentials are missing, wrong or lack permission.
+    Authentication,
+    /// The backend asked the caller to slow down.
+    RateLimited,
+    /// The backend failed or could not be reached.
+    Unavailable,
+    Other,
+}
+
+impl SpeechError {
+    pub fn new(kind: SpeechErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Whether the same request may succeed later.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind,
+            SpeechErrorKind::RateLimited | SpeechErrorKind::Unavailable
+        )
+    }
+}
+
+impl fmt::Display for SpeechError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+impl std::error::Error for SpeechError {}
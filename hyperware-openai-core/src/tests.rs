@@ -0,0 +1,690 @@
+use crate::api_error::{ApiErrorDetails, ApiErrorKind};
+use crate::audio::{id3v2_len, parse_wav, write_wav, InvalidWav};
+use crate::capabilities::ServerCapabilities;
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerPolicy, CircuitState};
+use crate::endpoint::{ApiProfile, Auth, Endpoint};
+use crate::headers::CustomHeaders;
+use crate::metadata::{RateLimitInfo, ResponseMetadata};
+use crate::rate_limit::{RateLimiter, Throttle, TokenBucket};
+use crate::retry::{
+    parse_reset_duration, send_streaming_with_retry, send_with_retry, server_retry_delay,
+    RetryPolicy,
+};
+use crate::speech::{
+    SpeechError, SpeechErrorKind, SpeechFuture, SpeechToText, SpeechToTextRequest, TimedText,
+    TimedTranscript,
+};
+use crate::transport::{HttpRequest, HttpTransport, InMemoryTransport, SleepFuture, TransportFuture};
+use http::{HeaderMap, HeaderValue, Method};
+use hyperware_process_lib::http::client::HttpClientError;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn request(path: &str) -> HttpRequest {
+    HttpRequest {
+        method: Method::POST,
+        url: url::Url::parse(&format!("https://api.openai.com{}", path)).unwrap(),
+        headers: HashMap::new(),
+        timeout: 60,
+        body: b"body".to_vec(),
+    }
+}
+
+#[tokio::test]
+async fn test_in_memory_transport_replays_in_order() {
+    let transport = Arc::new(InMemoryTransport::new());
+    transport.push_response(200, &[("content-type", "application/json")], r#"{"ok":true}"#);
+    transport.push_error(HttpClientError::ExecuteRequestFailed("offline".to_string()));
+
+    let shared: Arc<dyn HttpTransport> = transport.clone();
+    let first = shared.send(request("/first")).await.unwrap();
+    assert_eq!(first.status(), 200);
+    assert_eq!(first.headers()["content-type"], "application/json");
+    assert_eq!(first.body(), br#"{"ok":true}"#);
+
+    let second = shared.send(request("/second")).await;
+    assert!(matches!(second, Err(HttpClientError::ExecuteRequestFailed(_))));
+
+    // Nothing queued: the transport reports a failure instead of panicking
+    assert!(shared.send(request("/third")).await.is_err());
+
+    let paths: Vec<String> = transport
+        .requests()
+        .iter()
+        .map(|request| request.url.path().to_string())
+        .collect();
+    assert_eq!(paths, vec!["/first", "/second", "/third"]);
+    assert_eq!(transport.pending(), 0);
+}
+
+fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    for (name, value) in pairs {
+        map.insert(*name, HeaderValue::from_str(value).unwrap());
+    }
+    map
+}
+
+fn no_jitter() -> RetryPolicy {
+    RetryPolicy {
+        jitter: 0.0,
+        ..RetryPolicy::default()
+    }
+}
+
+#[test]
+fn test_parse_reset_duration() {
+    assert_eq!(parse_reset_duration("1s"), Some(Duration::from_secs(1)));
+    assert_eq!(parse_reset_duration("6m0s"), Some(Duration::from_secs(360)));
+    assert_eq!(parse_reset_duration("20ms"), Some(Duration::from_millis(20)));
+    assert_eq!(parse_reset_duration("1h2m3.5s"), Some(Duration::from_millis(3_723_500)));
+    assert_eq!(parse_reset_duration("2"), Some(Duration::from_secs(2)));
+    assert_eq!(parse_reset_duration(""), None);
+    assert_eq!(parse_reset_duration("soon"), None);
+    assert_eq!(parse_reset_duration("5x"), None);
+    assert_eq!(parse_reset_duration("99999999999999999999999h"), None);
+    assert_eq!(parse_reset_duration("1e400s"), None);
+}
+
+#[test]
+fn test_server_retry_delay_precedence() {
+    let map = headers(&[("retry-after-ms", "250"), ("retry-after", "3")]);
+    assert_eq!(server_retry_delay(&map), Some(Duration::from_millis(250)));
+
+    let map = headers(&[("retry-after", "3")]);
+    assert_eq!(server_retry_delay(&map), Some(Duration::from_secs(3)));
+
+    // Reset headers only count for limits that are exhausted
+    let map = headers(&[
+        ("x-ratelimit-remaining-requests", "0"),
+        ("x-ratelimit-reset-requests", "1s"),
+        ("x-ratelimit-remaining-tokens", "0"),
+        ("x-ratelimit-reset-tokens", "6m0s"),
+    ]);
+    assert_eq!(server_retry_delay(&map), Some(Duration::from_secs(360)));
+
+    let map = headers(&[
+        ("x-ratelimit-remaining-requests", "0"),
+        ("x-ratelimit-reset-requests", "1s"),
+        ("x-ratelimit-remaining-tokens", "9000"),
+        ("x-ratelimit-reset-tokens", "6m0s"),
+    ]);
+    assert_eq!(server_retry_delay(&map), Some(Duration::from_secs(1)));
+
+    assert_eq!(server_retry_delay(&headers(&[("retry-after", "later")])), None);
+
+    // Values too large for a Duration are ignored instead of panicking
+    assert_eq!(server_retry_delay(&headers(&[("retry-after", "1e30")])), None);
+    assert_eq!(server_retry_delay(&headers(&[("retry-after", "-1")])), None);
+    let map = headers(&[("retry-after-ms", "1e30"), ("retry-after", "2")]);
+    assert_eq!(server_retry_delay(&map), Some(Duration::from_secs(2)));
+    let map = headers(&[
+        ("x-ratelimit-remaining-requests", "0"),
+        ("x-ratelimit-reset-requests", "99999999999999999999999h"),
+    ]);
+    assert_eq!(server_retry_delay(&map), None);
+}
+
+#[test]
+fn test_backoff_grows_and_is_capped() {
+    let policy = RetryPolicy {
+        max_delay: Duration::from_secs(3),
+        ..no_jitter()
+    };
+    assert_eq!(policy.backoff(1), Duration::from_millis(500));
+    assert_eq!(policy.backoff(2), Duration::from_secs(1));
+    assert_eq!(policy.backoff(3), Duration::from_secs(2));
+    assert_eq!(policy.backoff(4), Duration::from_secs(3));
+    assert_eq!(policy.backoff(100), Duration::from_secs(3));
+
+    // Server-provided delays are capped as well
+    let map = headers(&[("retry-after", "60")]);
+    assert_eq!(policy.delay_for(1, Some(&map)), Duration::from_secs(3));
+    let map = headers(&[("retry-after", "1e18")]);
+    assert_eq!(policy.delay_for(1, Some(&map)), Duration::from_secs(3));
+    let map = headers(&[("retry-after-ms", "1e30")]);
+    assert!(policy.delay_for(1, Some(&map)) <= Duration::from_secs(3));
+
+    let jittered = RetryPolicy::default();
+    for _ in 0..50 {
+        let delay = jittered.backoff(2);
+        assert!(delay <= Duration::from_secs(1));
+        assert!(delay >= Duration::from_millis(800));
+    }
+}
+
+#[tokio::test]
+async fn test_send_with_retry_retries_until_success() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(429, &[("retry-after", "2")], "slow down");
+    transport.push_error(HttpClientError::ExecuteRequestFailed("reset".to_string()));
+    transport.push_response(200, &[], "ok");
+
+    let response = send_with_retry(&transport, &no_jitter(), None, request("/retry"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(transport.requests().len(), 3);
+    assert_eq!(
+        transport.sleeps(),
+        vec![Duration::from_secs(2), Duration::from_secs(1)]
+    );
+}
+
+#[tokio::test]
+async fn test_send_with_retry_gives_up() {
+    let transport = InMemoryTransport::new();
+    for _ in 0..3 {
+        transport.push_response(503, &[], "overloaded");
+    }
+    let response = send_with_retry(&transport, &no_jitter(), None, request("/retry"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 503);
+    assert_eq!(transport.requests().len(), 3);
+    assert_eq!(transport.sleeps().len(), 2);
+
+    // Non-retryable statuses and errors are returned immediately
+    let transport = InMemoryTransport::new();
+    transport.push_response(400, &[], "bad request");
+    transport.push_error(HttpClientError::BadUrl { url: "x".to_string() });
+    let response = send_with_retry(&transport, &no_jitter(), None, request("/retry"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let result = send_with_retry(&transport, &no_jitter(), None, request("/retry")).await;
+    assert!(matches!(result, Err(HttpClientError::BadUrl { .. })));
+    assert!(transport.sleeps().is_empty());
+
+    // Exhausted quota is not retried, unlike other 429s
+    let transport = InMemoryTransport::new();
+    transport.push_response(
+        429,
+        &[],
+        r#"{"error": {"message": "Out of credits", "type": "insufficient_quota", "code": "insufficient_quota"}}"#,
+    );
+    let response = send_with_retry(&transport, &no_jitter(), None, request("/retry"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 429);
+    assert_eq!(transport.requests().len(), 1);
+    assert!(transport.sleeps().is_empty());
+
+    // The default client policy sends once
+    let transport = InMemoryTransport::new();
+    transport.push_response(503, &[], "overloaded");
+    let response = send_with_retry(&transport, &RetryPolicy::none(), None, request("/retry"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 503);
+    assert_eq!(transport.pending(), 0);
+}
+
+/// Implements only `send`, to exercise the buffered `send_streaming`.
+struct BufferedTransport(InMemoryTransport);
+
+impl HttpTransport for BufferedTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        self.0.send(request)
+    }
+
+    fn sleep(&self, duration: Duration) -> SleepFuture<'_> {
+        self.0.sleep(duration)
+    }
+}
+
+#[tokio::test]
+async fn test_send_with_retry_throttles_every_attempt() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(503, &[], "overloaded");
+    transport.push_response(200, &[], "ok");
+
+    // One request per minute: the retry waits for a second token
+    let limiter = RateLimiter::new().with_requests_per_minute(1);
+    let throttle = Throttle::new(&limiter, 0.0);
+    let response = send_with_retry(&transport, &no_jitter(), Some(throttle), request("/retry"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let sleeps = transport.sleeps();
+    assert_eq!(sleeps.len(), 2);
+    assert_eq!(sleeps[0], Duration::from_millis(500));
+    assert!(sleeps[1] > Duration::from_secs(59), "{:?}", sleeps[1]);
+}
+
+#[tokio::test]
+async fn test_send_streaming_with_retry_delivers_chunks() {
+    let transport = InMemoryTransport::new();
+    transport.push_response(503, &[], "overloaded");
+    transport.push_chunked_response(200, &[("content-type", "audio/mpeg")], ["ab", "", "c"]);
+
+    let mut chunks = Vec::new();
+    let response = send_streaming_with_retry(
+        &transport,
+        &no_jitter(),
+        None,
+        request("/stream"),
+        &mut |headers, chunk| {
+            assert_eq!(headers["content-type"], "audio/mpeg");
+            chunks.push(chunk.to_vec());
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.body().is_empty());
+    assert_eq!(chunks, vec![b"ab".to_vec(), b"c".to_vec()]);
+    assert_eq!(transport.requests().len(), 2);
+
+    // Error bodies are returned, not streamed; `send` joins the chunks
+    transport.push_response(400, &[], "bad request");
+    transport.push_chunked_response(200, &[], ["ab", "c"]);
+    let mut streamed = false;
+    let response = send_streaming_with_retry(
+        &transport,
+        &no_jitter(),
+        None,
+        request("/stream"),
+        &mut |_, _| streamed = true,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.body(), b"bad request");
+    assert!(!streamed);
+    assert_eq!(transport.send(request("/")).await.unwrap().body(), b"abc");
+
+    // Without an incremental transport the whole body is one chunk
+    let buffered = BufferedTransport(InMemoryTransport::new());
+    buffered.0.push_chunked_response(200, &[], ["ab", "c"]);
+    let mut chunks = Vec::new();
+    let response = send_streaming_with_retry(
+        &buffered,
+        &RetryPolicy::none(),
+        None,
+        request("/stream"),
+        &mut |_, chunk| chunks.push(chunk.to_vec()),
+    )
+    .await
+    .unwrap();
+    assert!(response.body().is_empty());
+    assert_eq!(chunks, vec![b"abc".to_vec()]);
+}
+
+#[test]
+fn test_api_error_details_from_response() {
+    let map = headers(&[("x-request-id", "req_abc")]);
+    let details = ApiErrorDetails::from_response(
+        429,
+        &map,
+        br#"{"error": {"message": "Quota", "type": "insufficient_quota", "code": "insufficient_quota", "param": null}}"#,
+    );
+    assert_eq!(details.kind(), ApiErrorKind::QuotaExceeded);
+    assert_eq!(details.request_id.as_deref(), Some("req_abc"));
+    assert_eq!(details.param, None);
+
+    // Numeric codes are kept as text, and non-JSON bodies become the message
+    let details = ApiErrorDetails::from_response(
+        400,
+        &HeaderMap::new(),
+        br#"{"error": {"message": "Bad", "code": 1234}}"#,
+    );
+    assert_eq!(details.code.as_deref(), Some("1234"));
+    assert_eq!(details.kind(), ApiErrorKind::InvalidRequest);
+
+    let details = ApiErrorDetails::from_response(502, &HeaderMap::new(), b"Bad Gateway");
+    assert_eq!(details.message, "Bad Gateway");
+    assert_eq!(details.error_type, None);
+    assert_eq!(details.kind(), ApiErrorKind::ServerError);
+    assert_eq!(details.to_string(), "502 - Bad Gateway");
+
+    let details = ApiErrorDetails::from_response(409, &HeaderMap::new(), b"conflict");
+    assert_eq!(details.kind(), ApiErrorKind::Other);
+}
+
+#[test]
+fn test_response_metadata_from_headers() {
+    let map = headers(&[
+        ("x-request-id", "req_123"),
+        ("openai-processing-ms", "842"),
+        ("openai-model", "gpt-4o-mini-tts"),
+        ("x-ratelimit-limit-requests", "500"),
+        ("x-ratelimit-limit-tokens", "2000000"),
+        ("x-ratelimit-remaining-requests", "499"),
+        ("x-ratelimit-remaining-tokens", "1999000"),
+        ("x-ratelimit-reset-requests", "120ms"),
+        ("x-ratelimit-reset-tokens", "1m30s"),
+    ]);
+    let metadata = ResponseMetadata::from_headers(&map);
+    assert_eq!(metadata.request_id.as_deref(), Some("req_123"));
+    assert_eq!(metadata.processing_ms, Some(842));
+    assert_eq!(metadata.model.as_deref(), Some("gpt-4o-mini-tts"));
+    assert_eq!(
+        metadata.rate_limit,
+        RateLimitInfo {
+            limit_requests: Some(500),
+            limit_tokens: Some(2_000_000),
+            remaining_requests: Some(499),
+            remaining_tokens: Some(1_999_000),
+            reset_requests: Some(Duration::from_millis(120)),
+            reset_tokens: Some(Duration::from_secs(90)),
+        }
+    );
+
+    // Malformed or huge reset values are dropped, not fatal to a successful response
+    let map = headers(&[
+        ("x-request-id", "req_456"),
+        ("x-ratelimit-remaining-requests", "0"),
+        ("x-ratelimit-reset-requests", "99999999999999999999999h"),
+        ("x-ratelimit-reset-tokens", "soon"),
+    ]);
+    let metadata = ResponseMetadata::from_headers(&map);
+    assert_eq!(metadata.request_id.as_deref(), Some("req_456"));
+    assert_eq!(metadata.rate_limit.remaining_requests, Some(0));
+    assert_eq!(metadata.rate_limit.reset_requests, None);
+    assert_eq!(metadata.rate_limit.reset_tokens, None);
+
+    let metadata = ResponseMetadata::from_headers(&headers(&[("openai-processing-ms", "oops")]));
+    assert_eq!(metadata, ResponseMetadata::default());
+    assert!(metadata.rate_limit.is_empty());
+}
+
+#[test]
+fn test_token_bucket_reserves_and_refills() {
+    let start = Instant::now();
+    let mut bucket = TokenBucket::per_minute_at(2.0, start);
+    assert_eq!(bucket.reserve_at(1.0, start), Duration::ZERO);
+    assert_eq!(bucket.reserve_at(1.0, start), Duration::ZERO);
+    // Two tokens per minute: the third request waits for 30 seconds of refill
+    assert_eq!(bucket.reserve_at(1.0, start), Duration::from_secs(30));
+    // And the fourth queues behind it
+    assert_eq!(bucket.reserve_at(1.0, start), Duration::from_secs(60));
+
+    let later = start + Duration::from_secs(90);
+    assert!((bucket.available_at(later) - 1.0).abs() < 1e-9);
+    // Refill never exceeds capacity
+    assert!((bucket.available_at(later + Duration::from_secs(600)) - 2.0).abs() < 1e-9);
+
+    // Oversized costs wait for a full bucket instead of forever
+    let mut audio = TokenBucket::per_minute_at(60.0, start);
+    assert_eq!(audio.reserve_at(600.0, start), Duration::ZERO);
+    assert_eq!(audio.reserve_at(600.0, start), Duration::from_secs(60));
+}
+
+#[test]
+fn test_rate_limiter_follows_headers() {
+    let start = Instant::now();
+    let limiter = RateLimiter::new().with_requests_per_minute(600);
+    assert_eq!(limiter.reserve_at(0.0, start), Duration::ZERO);
+
+    // The server says the budget is spent for another two seconds
+    let exhausted = RateLimitInfo {
+        remaining_requests: Some(0),
+        reset_requests: Some(Duration::from_secs(2)),
+        ..RateLimitInfo::default()
+    };
+    limiter.observe_at(&exhausted, start);
+    let wait = limiter.reserve_at(0.0, start);
+    assert!((wait.as_secs_f64() - 2.0).abs() < 1e-6, "{:?}", wait);
+
+    // Requests and audio are limited independently; the longer wait wins
+    let limiter = RateLimiter::new()
+        .with_requests_per_minute(600)
+        .with_audio_seconds_per_minute(60.0);
+    assert_eq!(limiter.reserve_at(45.0, start), Duration::ZERO);
+    assert_eq!(limiter.reserve_at(45.0, start), Duration::from_secs(30));
+
+    // Corrections after the fact take from or return to the audio budget
+    let limiter = RateLimiter::new().with_audio_seconds_per_minute(60.0);
+    assert!(limiter.limits_audio());
+    limiter.charge_audio_at(75.0, start);
+    limiter.charge_audio_at(-30.0, start);
+    assert_eq!(limiter.reserve_at(45.0, start), Duration::from_secs(15));
+
+    // Without configured buckets nothing is throttled
+    let unlimited = RateLimiter::new();
+    assert!(!unlimited.limits_audio());
+    unlimited.observe_at(&exhausted, start);
+    assert_eq!(unlimited.reserve_at(1000.0, start), Duration::ZERO);
+}
+
+#[test]
+fn test_custom_headers_replace_case_insensitively() {
+    let mut custom = CustomHeaders::new();
+    custom.insert("OpenAI-Project", "proj_a");
+    custom.insert("openai-project", "proj_b");
+    custom.insert("X-Trace", "1");
+    assert_eq!(custom.get("OPENAI-PROJECT"), Some("proj_b"));
+    assert_eq!(custom.iter().count(), 2);
+
+    let mut headers = HashMap::new();
+    headers.insert("Authorization".to_string(), "Bearer key".to_string());
+    headers.insert("X-TRACE".to_string(), "0".to_string());
+    custom.apply_to(&mut headers);
+    assert_eq!(headers.len(), 3);
+    assert_eq!(headers["openai-project"], "proj_b");
+    assert_eq!(headers["X-Trace"], "1");
+    assert_eq!(headers["Authorization"], "Bearer key");
+}
+
+#[test]
+fn test_endpoint_urls_and_auth() {
+    let openai = ApiProfile::openai("sk-test");
+    assert_eq!(
+        openai.endpoint.url("audio/speech").unwrap().as_str(),
+        "https://api.openai.com/v1/audio/speech"
+    );
+
+    let mut azure = ApiProfile::azure_api_key(
+        "https://my-resource.openai.azure.com/",
+        "whisper",
+        "2024-06-01",
+        "azure-key",
+    );
+    assert_eq!(
+        azure.endpoint.url("audio/transcriptions").unwrap().as_str(),
+        "https://my-resource.openai.azure.com/openai/deployments/whisper/audio/transcriptions?api-version=2024-06-01"
+    );
+    assert!(!azure.endpoint.enforces_openai_models());
+    let spaced = Endpoint::Azure {
+        resource_url: "https://r.openai.azure.com".to_string(),
+        deployment: "my model/v2".to_string(),
+        api_version: "2024-06-01".to_string(),
+    };
+    assert_eq!(
+        spaced.url("/audio/speech").unwrap().as_str(),
+        "https://r.openai.azure.com/openai/deployments/my%20model%2Fv2/audio/speech?api-version=2024-06-01"
+    );
+    azure.endpoint.set_base_url("https://proxy.internal");
+    assert!(matches!(
+        &azure.endpoint,
+        Endpoint::Azure { resource_url, deployment, .. }
+            if resource_url == "https://proxy.internal" && deployment == "whisper"
+    ));
+
+    let mut headers = HashMap::new();
+    azure.auth.apply_to(&mut headers);
+    assert_eq!(headers["api-key"], "azure-key");
+    assert!(!headers.contains_key("Authorization"));
+
+    let aad = ApiProfile::azure_ad_token("https://r.openai.azure.com", "tts", "2025-03-01", "eyJ0");
+    let mut headers = HashMap::new();
+    aad.auth.apply_to(&mut headers);
+    assert_eq!(headers["Authorization"], "Bearer eyJ0");
+
+    let compatible = ApiProfile::compatible("http://localhost:8000", "");
+    assert_eq!(
+        compatible.endpoint.url("/audio/speech").unwrap().as_str(),
+        "http://localhost:8000/v1/audio/speech"
+    );
+    assert!(compatible.auth.is_missing());
+    assert!(!Auth::ApiKey("k".to_string()).is_missing());
+}
+
+#[test]
+fn test_keyless_profile_with_path_prefix() {
+    let profile = ApiProfile::keyless("http://whisper.local:9000/").with_path_prefix("/api/v1/");
+    assert_eq!(
+        profile.endpoint.url("audio/transcriptions").unwrap().as_str(),
+        "http://whisper.local:9000/api/v1/audio/transcriptions"
+    );
+    assert!(!profile.endpoint.enforces_openai_models());
+    assert!(!profile.auth.is_missing());
+    let mut headers = HashMap::new();
+    profile.auth.apply_to(&mut headers);
+    assert!(headers.is_empty());
+
+    let bare = ApiProfile::keyless("http://tts.local").with_path_prefix("");
+    assert_eq!(bare.endpoint.url("models").unwrap().as_str(), "http://tts.local/models");
+
+    // Prefixes only apply to compatible servers
+    assert_eq!(ApiProfile::openai("k").with_path_prefix("/x"), ApiProfile::openai("k"));
+}
+
+#[test]
+fn test_server_capabilities_parsing() {
+    let models = ServerCapabilities::parse_models(
+        br#"{"object": "list", "data": [{"id": "Systran/faster-whisper-small"}, {"id": "kokoro"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(models, vec!["Systran/faster-whisper-small", "kokoro"]);
+    assert!(ServerCapabilities::parse_models(b"not json").is_err());
+
+    assert_eq!(
+        ServerCapabilities::parse_voices(br#"["af_heart", "am_adam"]"#),
+        Some(vec!["af_heart".to_string(), "am_adam".to_string()])
+    );
+    assert_eq!(
+        ServerCapabilities::parse_voices(br#"{"voices": [{"voice_id": "v1"}, {"name": "v2"}]}"#),
+        Some(vec!["v1".to_string(), "v2".to_string()])
+    );
+    assert_eq!(ServerCapabilities::parse_voices(b"<html>"), None);
+
+    let capabilities = ServerCapabilities {
+        models,
+        voices: Some(vec!["af_heart".to_string()]),
+        formats: Some(vec!["wav".to_string(), "pcm".to_string()]),
+    };
+    assert!(capabilities.supports_model("kokoro"));
+    assert!(!capabilities.supports_model("tts-1"));
+    assert!(capabilities.supports_voice("AF_HEART"));
+    assert!(!capabilities.supports_voice("alloy"));
+    assert!(capabilities.supports_format("WAV"));
+    assert!(!capabilities.supports_format("mp3"));
+    assert!(ServerCapabilities::default().supports_model("anything"));
+    assert!(ServerCapabilities::default().supports_voice("anything"));
+    assert!(ServerCapabilities::default().supports_format("anything"));
+}
+
+/// A stand-in backend, as application tests would use in place of a client.
+struct EchoTranscriber;
+
+impl SpeechToText for EchoTranscriber {
+    fn speech_to_text(&self, request: SpeechToTextRequest) -> SpeechFuture<'_, TimedTranscript> {
+        Box::pin(async move {
+            if request.audio.is_empty() {
+                return Err(SpeechError::new(SpeechErrorKind::InvalidRequest, "no audio"));
+            }
+            let text = String::from_utf8_lossy(&request.audio).to_string();
+            Ok(TimedTranscript {
+                segments: vec![TimedText {
+                    start: 0.0,
+                    end: 1.0,
+                    text: text.clone(),
+                }],
+                text,
+                ..TimedTranscript::default()
+            })
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_speech_to_text_trait_object() {
+    let backend: Box<dyn SpeechToText> = Box::new(EchoTranscriber);
+    let transcript = backend
+        .speech_to_text(SpeechToTextRequest {
+            audio: b"hello".to_vec(),
+            ..SpeechToTextRequest::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(transcript.text, "hello");
+    assert_eq!(transcript.segments[0].end, 1.0);
+
+    let error = backend
+        .speech_to_text(SpeechToTextRequest::default())
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind, SpeechErrorKind::InvalidRequest);
+    assert!(!error.is_retryable());
+    assert!(SpeechError::new(SpeechErrorKind::Unavailable, "down").is_retryable());
+}
+
+#[test]
+fn test_circuit_breaker_opens_and_recovers() {
+    let breaker = CircuitBreaker::new(CircuitBreakerPolicy {
+        window: 4,
+        minimum_requests: 4,
+        open_duration: Duration::from_secs(10),
+        ..CircuitBreakerPolicy::default()
+    });
+    let endpoint = ApiProfile::openai("k").endpoint;
+    let other = ApiProfile::keyless("http://localhost:8000").endpoint;
+    let down = SpeechError::new(SpeechErrorKind::Unavailable, "503");
+    let rejected = SpeechError::new(SpeechErrorKind::InvalidRequest, "400");
+    let now = Instant::now();
+
+    // Client errors show the endpoint is up; one failure in four stays closed
+    breaker.record_at(&endpoint, None, now);
+    breaker.record_at(&endpoint, Some(&rejected), now);
+    breaker.record_at(&endpoint, Some(&down), now);
+    breaker.record_at(&endpoint, None, now);
+    assert_eq!(breaker.state(&endpoint), CircuitState::Closed);
+    breaker.record_at(&endpoint, Some(&down), now);
+    let status = breaker.status_at(&endpoint, now + Duration::from_secs(4));
+    assert_eq!(status.state, CircuitState::Open);
+    assert_eq!(status.retry_after, Duration::from_secs(6));
+    assert_eq!(breaker.try_acquire_at(&endpoint, now), Err(Duration::from_secs(10)));
+    assert_eq!(breaker.try_acquire_at(&other, now), Ok(()));
+
+    // One probe at a time while half-open; a failed probe reopens the circuit
+    let later = now + Duration::from_secs(10);
+    assert_eq!(breaker.try_acquire_at(&endpoint, later), Ok(()));
+    assert_eq!(breaker.state(&endpoint), CircuitState::HalfOpen);
+    assert!(breaker.try_acquire_at(&endpoint, later).is_err());
+    breaker.record_at(&endpoint, Some(&down), later);
+    assert_eq!(breaker.state(&endpoint), CircuitState::Open);
+
+    let recovered = later + Duration::from_secs(10);
+    assert_eq!(breaker.try_acquire_at(&endpoint, recovered), Ok(()));
+    breaker.record_at(&endpoint, None, recovered);
+    let status = breaker.status_at(&endpoint, recovered);
+    assert_eq!(status.state, CircuitState::Closed);
+    assert_eq!((status.requests, status.failures), (0, 0));
+    assert_eq!(breaker.statuses().len(), 1);
+    assert_eq!(endpoint.to_string(), "https://api.openai.com/v1");
+}
+
+#[test]
+fn test_wav_round_trip_and_id3_length() {
+    // 8 kHz mono 16-bit
+    let fmt = [1, 0, 1, 0, 0x40, 0x1F, 0, 0, 0x80, 0x3E, 0, 0, 2, 0, 16, 0];
+    let wav = write_wav(&fmt, &[0; 8000]);
+    let parsed = parse_wav(&wav).unwrap();
+    assert_eq!(parsed.fmt, &fmt);
+    assert_eq!(parsed.sample_rate, 8000);
+    assert_eq!(parsed.block_align, 2);
+    assert_eq!(parsed.duration(), 0.5);
+
+    assert_eq!(parse_wav(b"not a wav file"), Err(InvalidWav("missing RIFF/WAVE header")));
+    let truncated = &wav[..wav.len() - 8000 - 8];
+    assert_eq!(parse_wav(truncated), Err(InvalidWav("missing data chunk")));
+
+    // A 20-byte tag with a footer, as a synchsafe size
+    let mut tagged = b"ID3\x04\x00\x10\x00\x00\x00\x14".to_vec();
+    tagged.extend_from_slice(&[0; 40]);
+    assert_eq!(id3v2_len(&tagged), 40);
+    assert_eq!(id3v2_len(b"fLaC"), 0);
+}
@@ -0,0 +1,249 @@
+use http::{HeaderMap, Method};
+use hyperware_process_lib::http::client::{send_request_await_response, HttpClientError};
+use hyperware_process_lib::hyperapp;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// An outgoing HTTP request, as handed to an [`HttpTransport`].
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: url::Url,
+    pub headers: HashMap<String, String>,
+    /// Timeout passed through to the transport.
+    pub timeout: u64,
+    pub body: Vec<u8>,
+}
+
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<http::Response<Vec<u8>>, HttpClientError>> + 'a>>;
+
+pub type SleepFuture<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+/// Receives the body of a successful response, piece by piece as it arrives,
+/// together with the response headers.
+pub type BodySink<'a> = dyn FnMut(&HeaderMap, &[u8]) + 'a;
+
+/// Sends HTTP requests on behalf of the API clients.
+///
+/// [`HyperwareTransport`] is used by default; [`InMemoryTransport`] lets the
+/// clients run in `cargo test` without a Hyperware node.
+pub trait HttpTransport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_>;
+
+    /// Sends `request` and hands the body of a successful (2xx) response to
+    /// `on_chunk` as it arrives; the returned response then has an empty body.
+    /// Other responses are returned with their body and `on_chunk` is not called.
+    ///
+    /// The default waits for [`send`](Self::send) and hands over the whole
+    /// body at once. Transports that can read a response incrementally
+    /// override it.
+    fn send_streaming<'a>(
+        &'a self,
+        request: HttpRequest,
+        on_chunk: &'a mut BodySink<'_>,
+    ) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let mut response = self.send(request).await?;
+            if response.status().is_success() {
+                let body = std::mem::take(response.body_mut());
+                if !body.is_empty() {
+                    on_chunk(response.headers(), &body);
+                }
+            }
+            Ok(response)
+        })
+    }
+
+    /// Waits between retries.
+    fn sleep(&self, duration: Duration) -> SleepFuture<'_>;
+}
+
+impl<T: HttpTransport + ?Sized> HttpTransport for Arc<T> {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        (**self).send(request)
+    }
+
+    fn send_streaming<'a>(
+        &'a self,
+        request: HttpRequest,
+        on_chunk: &'a mut BodySink<'_>,
+    ) -> TransportFuture<'a> {
+        (**self).send_streaming(request, on_chunk)
+    }
+
+    fn sleep(&self, duration: Duration) -> SleepFuture<'_> {
+        (**self).sleep(duration)
+    }
+}
+
+impl<T: HttpTransport + ?Sized> HttpTransport for Box<T> {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        (**self).send(request)
+    }
+
+    fn send_streaming<'a>(
+        &'a self,
+        request: HttpRequest,
+        on_chunk: &'a mut BodySink<'_>,
+    ) -> TransportFuture<'a> {
+        (**self).send_streaming(request, on_chunk)
+    }
+
+    fn sleep(&self, duration: Duration) -> SleepFuture<'_> {
+        (**self).sleep(duration)
+    }
+}
+
+/// Sends requests through the Hyperware runtime's HTTP client and waits on
+/// the runtime's timer, both without blocking the process.
+///
+/// The runtime returns response bodies complete, so
+/// [`send_streaming`](HttpTransport::send_streaming) delivers them in one
+/// chunk once the download has finished.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HyperwareTransport;
+
+impl HttpTransport for HyperwareTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            send_request_await_response(
+                request.method,
+                request.url,
+                Some(request.headers),
+                request.timeout,
+                request.body,
+            )
+            .await
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> SleepFuture<'_> {
+        let millis = duration.as_millis().min(u64::MAX as u128) as u64;
+        Box::pin(async move {
+            // Yields to the process's other tasks until the timer fires. A failed
+            // timer only shortens the wait; the retry still happens
+            let _ = hyperapp::sleep(millis).await;
+        })
+    }
+}
+
+/// A response whose body is kept in the chunks `send_streaming` delivers it in.
+type ChunkedResponse = http::Response<Vec<Vec<u8>>>;
+
+/// Replays queued responses and records every request it receives.
+///
+/// Share it with a client through an `Arc` to inspect the recorded requests.
+/// Sleeps return immediately and are only recorded.
+#[derive(Debug, Default)]
+pub struct InMemoryTransport {
+    responses: Mutex<VecDeque<Result<ChunkedResponse, HttpClientError>>>,
+    requests: Mutex<Vec<HttpRequest>>,
+    sleeps: Mutex<Vec<Duration>>,
+}
+
+impl InMemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a response with the given status, headers and body.
+    pub fn push_response(&self, status: u16, headers: &[(&str, &str)], body: impl Into<Vec<u8>>) {
+        self.push_chunked_response(status, headers, [body]);
+    }
+
+    /// Queues a response whose body `send_streaming` delivers in `chunks`.
+    pub fn push_chunked_response<B: Into<Vec<u8>>>(
+        &self,
+        status: u16,
+        headers: &[(&str, &str)],
+        chunks: impl IntoIterator<Item = B>,
+    ) {
+        let mut builder = http::Response::builder().status(status);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        let response = builder
+            .body(chunks.into_iter().map(Into::into).collect())
+            .expect("invalid status or header for InMemoryTransport response");
+        self.responses.lock().unwrap().push_back(Ok(response));
+    }
+
+    /// Queues a transport-level failure.
+    pub fn push_error(&self, error: HttpClientError) {
+        self.push(Err(error));
+    }
+
+    pub fn push(&self, response: Result<http::Response<Vec<u8>>, HttpClientError>) {
+        let response = response.map(|response| response.map(|body| vec![body]));
+        self.responses.lock().unwrap().push_back(response);
+    }
+
+    /// Requests sent so far, oldest first.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn last_request(&self) -> Option<HttpRequest> {
+        self.requests.lock().unwrap().last().cloned()
+    }
+
+    /// Durations the transport was asked to sleep for, oldest first.
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps.lock().unwrap().clone()
+    }
+
+    /// Number of queued responses not yet consumed.
+    pub fn pending(&self) -> usize {
+        self.responses.lock().unwrap().len()
+    }
+}
+
+impl InMemoryTransport {
+    fn next_response(
+        &self,
+        request: HttpRequest,
+    ) -> Result<ChunkedResponse, HttpClientError> {
+        self.requests.lock().unwrap().push(request);
+        self.responses.lock().unwrap().pop_front().unwrap_or_else(|| {
+            Err(HttpClientError::ExecuteRequestFailed(
+                "InMemoryTransport has no queued response".to_string(),
+            ))
+        })
+    }
+}
+
+impl HttpTransport for InMemoryTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        let response = self
+            .next_response(request)
+            .map(|response| response.map(|chunks| chunks.concat()));
+        Box::pin(async move { response })
+    }
+
+    fn send_streaming<'a>(
+        &'a self,
+        request: HttpRequest,
+        on_chunk: &'a mut BodySink<'_>,
+    ) -> TransportFuture<'a> {
+        let response = self.next_response(request);
+        Box::pin(async move {
+            let (parts, chunks) = response?.into_parts();
+            if !parts.status.is_success() {
+                return Ok(http::Response::from_parts(parts, chunks.concat()));
+            }
+            for chunk in chunks.iter().filter(|chunk| !chunk.is_empty()) {
+                on_chunk(&parts.headers, chunk);
+            }
+            Ok(http::Response::from_parts(parts, Vec::new()))
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> SleepFuture<'_> {
+        self.sleeps.lock().unwrap().push(duration);
+        Box::pin(async {})
+    }
+}
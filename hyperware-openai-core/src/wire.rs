@@ -0,0 +1,36 @@
+/// Implements `Display`, `Serialize` and `Deserialize` for enums that go over
+/// the wire by name, through their `as_str()` and `FromStr` implementations.
+///
+/// Used for the model, voice and format enums of both clients, whose
+/// `FromStr` keeps unknown names in a `Custom` variant.
+#[macro_export]
+macro_rules! impl_wire_name {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl ::std::fmt::Display for $name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.write_str(self.as_str())
+                }
+            }
+
+            impl $crate::__serde::Serialize for $name {
+                fn serialize<S: $crate::__serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_str(self.as_str())
+                }
+            }
+
+            impl<'de> $crate::__serde::Deserialize<'de> for $name {
+                fn deserialize<D: $crate::__serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Self, D::Error> {
+                    let name = <String as $crate::__serde::Deserialize>::deserialize(deserializer)?;
+                    name.parse()
+                        .map_err(<D::Error as $crate::__serde::de::Error>::custom)
+                }
+            }
+        )*
+    };
+}
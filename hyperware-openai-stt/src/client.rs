@@ -1,28 +1,63 @@
 use crate::error::SttError;
-use crate::multipart::{get_content_type_for_extension, MultipartFormData};
-use crate::types::{ApiErrorResponse, Model, ResponseFormat, TranscriptionRequest, TranscriptionResponse};
-use hyperware_process_lib::http::client::send_request_await_response;
+use crate::multipart::{prepare_audio_file, MultipartFormData};
+use crate::splitting::{self, PcmSpec, SplitOptions, MAX_UPLOAD_BYTES};
+use crate::types::{
+    ChunkingStrategy, Model, ResponseFormat, TextTranscription, TimestampGranularity,
+    TranscriptDone, Transcription, TranscriptionInclude, TranscriptionRequest,
+    TranscriptionResponse, TranscriptionStreamEvent, TranslationRequest, VerboseTranscription,
+};
+use hyperware_openai_core::api_error::ApiErrorDetails;
+use hyperware_openai_core::capabilities::{ServerCapabilities, MODELS_ROUTE};
+use hyperware_openai_core::circuit_breaker::{CircuitBreaker, CircuitStatus};
+use hyperware_openai_core::endpoint::{ApiProfile, Endpoint};
+use hyperware_openai_core::fallback::{FallbackPolicy, FallbackResponse, ServedBy};
+use hyperware_openai_core::headers::{CustomHeaders, ORGANIZATION_HEADER, PROJECT_HEADER};
+use hyperware_openai_core::metadata::ResponseMetadata;
+use hyperware_openai_core::rate_limit::{RateLimiter, Throttle};
+use hyperware_openai_core::retry::{send_streaming_with_retry, send_with_retry, RetryPolicy};
+use hyperware_openai_core::speech::SpeechError;
+use hyperware_openai_core::sse::SseParser;
+use hyperware_openai_core::transport::{BodySink, HttpRequest, HttpTransport, HyperwareTransport};
 use hyperware_process_lib::http::client::HttpClientError;
-use http::Method;
+use http::{HeaderMap, Method};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 pub struct TranscriptionClient {
-    api_key: String,
-    base_url: String,
+    profile: ApiProfile,
     timeout: u64,
+    transport: Arc<dyn HttpTransport>,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    default_headers: CustomHeaders,
+    capabilities: Option<ServerCapabilities>,
 }
 
 impl TranscriptionClient {
+    /// A client for OpenAI's API.
     pub fn new(api_key: impl Into<String>) -> Self {
+        Self::from_profile(ApiProfile::openai(api_key))
+    }
+
+    /// A client for the endpoint and credentials of `profile`, e.g. an Azure
+    /// OpenAI deployment.
+    pub fn from_profile(profile: ApiProfile) -> Self {
         Self {
-            api_key: api_key.into(),
-            base_url: "https://api.openai.com".to_string(),
+            profile,
             timeout: 60000, // 60 seconds default
+            transport: Arc::new(HyperwareTransport),
+            retry_policy: RetryPolicy::none(),
+            rate_limiter: None,
+            circuit_breaker: None,
+            default_headers: CustomHeaders::new(),
+            capabilities: None,
         }
     }
 
+    /// Replaces the host of the endpoint, e.g. to go through a proxy.
     pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
-        self.base_url = base_url.into();
+        self.profile.endpoint.set_base_url(base_url);
         self
     }
 
@@ -31,31 +66,301 @@ impl TranscriptionClient {
         self
     }
 
-    pub fn transcribe(&self) -> TranscriptionRequestBuilder {
+    /// Replaces the Hyperware HTTP client, e.g. with an `InMemoryTransport` in tests.
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Retries rate-limited, overloaded and failed requests. Requests are sent
+    /// once by default.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Delays requests to stay within `rate_limiter`, which may be shared with
+    /// other clients using the same account.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Fails requests at once with `SttError::CircuitOpen` while
+    /// `circuit_breaker` reports their endpoint as failing. The breaker may be
+    /// shared with other clients.
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// The circuit of the client's endpoint, if a circuit breaker is set.
+    pub fn circuit_status(&self) -> Option<CircuitStatus> {
+        self.circuit_breaker
+            .as_ref()
+            .map(|breaker| breaker.status(&self.profile.endpoint))
+    }
+
+    /// Adds a header to every request. Headers set on a request builder take
+    /// precedence.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    /// Bills requests to this organization (`OpenAI-Organization`).
+    pub fn with_organization(self, organization: impl Into<String>) -> Self {
+        self.with_header(ORGANIZATION_HEADER, organization)
+    }
+
+    /// Bills requests to this project (`OpenAI-Project`).
+    pub fn with_project(self, project: impl Into<String>) -> Self {
+        self.with_header(PROJECT_HEADER, project)
+    }
+
+    /// Rejects requests for models or response formats the server does not
+    /// list, before sending them. See [`probe_capabilities`](Self::probe_capabilities).
+    pub fn with_capabilities(mut self, capabilities: ServerCapabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    pub fn transcribe(&self) -> TranscriptionRequestBuilder<'_> {
         TranscriptionRequestBuilder {
             client: self,
             request: TranscriptionRequest::default(),
         }
     }
 
+    /// Translates audio in any supported language into English text.
+    pub fn translate(&self) -> TranslationRequestBuilder<'_> {
+        TranslationRequestBuilder {
+            client: self,
+            request: TranslationRequest::default(),
+        }
+    }
+
+    /// Asks the server which models it serves, for
+    /// [`with_capabilities`](Self::with_capabilities).
+    pub async fn probe_capabilities(&self) -> Result<ServerCapabilities, SttError> {
+        let body = self.get(MODELS_ROUTE).await?;
+        let models = ServerCapabilities::parse_models(&body)
+            .map_err(|e| SttError::ParseError(e.to_string()))?;
+        Ok(ServerCapabilities {
+            models,
+            voices: None,
+            formats: None,
+        })
+    }
+
+    /// Whether `model` is known to answer in `verbose_json`: whisper-1 is,
+    /// and a custom model is when the capabilities list the format.
+    pub(crate) fn supports_verbose_json(&self, model: &Model) -> bool {
+        match model {
+            Model::Whisper1 => true,
+            Model::Gpt4oTranscribe | Model::Gpt4oMiniTranscribe => false,
+            Model::Custom(_) => self
+                .capabilities
+                .as_ref()
+                .filter(|capabilities| capabilities.formats.is_some())
+                .is_some_and(|capabilities| {
+                    capabilities.supports_format(ResponseFormat::VerboseJson.as_str())
+                }),
+        }
+    }
+
+    /// Checks `model` and `format` against the capabilities of the client's
+    /// endpoint and, for OpenAI, the model against `openai_rule`, which
+    /// returns why the model is unsuitable. `profile` overrides the client's
+    /// endpoint.
+    fn check_model(
+        &self,
+        profile: Option<&ApiProfile>,
+        model: &Model,
+        format: Option<&ResponseFormat>,
+        openai_rule: impl FnOnce(&Model) -> Option<String>,
+    ) -> Result<(), SttError> {
+        let capabilities = self.capabilities.as_ref().filter(|_| profile.is_none());
+        if let Some(capabilities) = capabilities {
+            if !capabilities.supports_model(model.as_str()) {
+                return Err(SttError::InvalidModel(format!(
+                    "{} is not served by this server",
+                    model.as_str()
+                )));
+            }
+            let format = format.filter(|format| !capabilities.supports_format(format.as_str()));
+            if let Some(format) = format {
+                return Err(SttError::InvalidParameter(format!(
+                    "response_format {} is not produced by this server",
+                    format.as_str()
+                )));
+            }
+        }
+        let profile = profile.unwrap_or(&self.profile);
+        if profile.endpoint.enforces_openai_models() {
+            if let Some(reason) = openai_rule(model) {
+                return Err(SttError::InvalidModel(reason));
+            }
+        }
+        Ok(())
+    }
+
     async fn send_transcription_request(
         &self,
         request: TranscriptionRequest,
-    ) -> Result<TranscriptionResponse, SttError> {
-        if request.file.is_empty() {
-            return Err(SttError::MissingFile);
+    ) -> Result<Transcription, SttError> {
+        let format = request.response_format.clone().unwrap_or(ResponseFormat::Json);
+        let response = self.post_transcription(request, None).await?;
+        self.finish_transcription(response, &format)
+    }
+
+    /// Sends `request`, then each target of `policy` in turn while the
+    /// failures are ones the policy falls back on.
+    async fn send_fallback_transcription_request(
+        &self,
+        request: TranscriptionRequest,
+        policy: &FallbackPolicy<Model>,
+    ) -> Result<FallbackResponse<Transcription>, SttError> {
+        let mut attempt = request.clone();
+        let mut index = 0;
+        loop {
+            let served_by = ServedBy {
+                index,
+                model: attempt.model.as_str().to_string(),
+                endpoint: attempt.profile.as_ref().unwrap_or(&self.profile).endpoint.clone(),
+            };
+            let error = match self.send_transcription_request(attempt).await {
+                Ok(response) => return Ok(FallbackResponse { response, served_by }),
+                Err(error) => error,
+            };
+
+            let Some(target) = policy.targets.get(index) else {
+                return Err(error);
+            };
+            if !policy.falls_back_on(&error.clone().into()) {
+                return Err(error);
+            }
+
+            attempt = request.clone();
+            attempt.model = target.model.clone();
+            attempt.profile = target.profile.clone();
+            if attempt.profile.as_ref().unwrap_or(&self.profile).endpoint.enforces_openai_models() {
+                adapt_to_model(&mut attempt);
+            }
+            index += 1;
         }
+    }
 
-        if self.api_key.is_empty() {
-            return Err(SttError::MissingApiKey);
+    async fn send_long_transcription_request(
+        &self,
+        mut request: TranscriptionRequest,
+        mut options: SplitOptions,
+    ) -> Result<Transcription, SttError> {
+        let format = request.response_format.clone().unwrap_or(ResponseFormat::Json);
+        if matches!(format, ResponseFormat::Srt | ResponseFormat::Vtt) {
+            return Err(SttError::InvalidParameter(
+                "long-audio transcription supports json, text and verbose_json".to_string(),
+            ));
         }
 
+        options.max_chunk_bytes = options.max_chunk_bytes.min(MAX_UPLOAD_BYTES);
+        if request.file.len() <= options.max_chunk_bytes {
+            return self.send_transcription_request(request).await;
+        }
+
+        // Without timestamps there is no way to drop the repeated audio
+        if !matches!(format, ResponseFormat::VerboseJson) {
+            options.overlap_seconds = 0.0;
+        }
+
+        let file = std::mem::take(&mut request.file);
+        let chunks = splitting::split_audio(&file, &request.file_name, &options)?;
+        drop(file);
+
+        let mut bounds = Vec::with_capacity(chunks.len());
+        let mut parts = Vec::with_capacity(chunks.len());
+        let mut prompt = request.prompt.clone();
+        for chunk in chunks {
+            bounds.push((chunk.start, chunk.start + chunk.duration));
+
+            let mut chunk_request = request.clone();
+            chunk_request.file = chunk.data;
+            chunk_request.file_name = chunk.file_name;
+            chunk_request.prompt = prompt.take();
+
+            let part = self.send_transcription_request(chunk_request).await?;
+            let tail = splitting::prompt_tail(part.text(), options.prompt_tail_chars);
+            prompt = splitting::chunk_prompt(request.prompt.as_deref(), &tail);
+            parts.push(part);
+        }
+
+        Ok(splitting::stitch_transcriptions(&bounds, parts))
+    }
+
+    async fn send_streaming_request(
+        &self,
+        mut request: TranscriptionRequest,
+        on_event: &mut dyn FnMut(&TranscriptionStreamEvent),
+    ) -> Result<TranscriptDone, SttError> {
+        let format = request.response_format.as_ref();
+        self.check_model(request.profile.as_ref(), &request.model, format, |model| {
+            matches!(model, Model::Whisper1)
+                .then(|| "whisper-1 does not support streaming".to_string())
+        })?;
+        request.stream = true;
+
+        let mut stream = TranscriptStream::new(on_event);
+        let response = self
+            .post_transcription(request, Some(&mut |headers, chunk| stream.feed(headers, chunk)))
+            .await?;
+        let audio = response.audio;
+        let done = stream.finish(response.metadata)?;
+        let reported = done.usage.as_ref().and_then(|usage| usage.seconds);
+        self.correct_audio_estimate(audio, reported);
+        Ok(done)
+    }
+
+    /// Validates the request and posts it to the transcriptions endpoint.
+    /// `on_chunk` receives the body of a successful response as it arrives.
+    async fn post_transcription(
+        &self,
+        request: TranscriptionRequest,
+        on_chunk: Option<&mut BodySink<'_>>,
+    ) -> Result<FormResponse, SttError> {
+        check_file_size(&request.file)?;
+
+        if !request.timestamp_granularities.is_empty()
+            && !matches!(request.response_format, Some(ResponseFormat::VerboseJson))
+        {
+            return Err(SttError::InvalidParameter(
+                "timestamp_granularities requires response_format verbose_json".to_string(),
+            ));
+        }
+
+        if let Some(strategy) = &request.chunking_strategy {
+            strategy.validate()?;
+        }
+
+        let logprobs = request.include.contains(&TranscriptionInclude::Logprobs);
+        let format = request.response_format.as_ref();
+        self.check_model(request.profile.as_ref(), &request.model, format, |model| {
+            (logprobs && matches!(model, Model::Whisper1))
+                .then(|| "logprobs are not supported by whisper-1".to_string())
+        })?;
+        if logprobs && !matches!(request.response_format, None | Some(ResponseFormat::Json)) {
+            return Err(SttError::InvalidParameter(
+                "logprobs require response_format json".to_string(),
+            ));
+        }
+
+        let audio = self.audio_charge(&request.file);
+
         // Build multipart form data
         let mut form = MultipartFormData::new();
         
-        // Add file
-        let content_type = get_content_type_for_extension(&request.file_name);
-        form.add_file("file", &request.file_name, content_type, request.file);
+        // Add file, named and typed after its detected container
+        let (file_name, content_type) = prepare_audio_file(&request.file, &request.file_name)?;
+        form.add_file("file", file_name, content_type, request.file);
         
         // Add model
         form.add_text("model", request.model.as_str());
@@ -77,55 +382,505 @@ impl TranscriptionClient {
             form.add_text("temperature", temperature.to_string());
         }
 
+        for granularity in &request.timestamp_granularities {
+            form.add_text("timestamp_granularities[]", granularity.as_str());
+        }
+
+        if let Some(strategy) = &request.chunking_strategy {
+            for (name, value) in strategy.form_fields() {
+                form.add_text(name, value);
+            }
+        }
+
+        for include in &request.include {
+            form.add_text("include[]", include.as_str());
+        }
+
+        if request.stream {
+            form.add_text("stream", "true");
+        }
+
+        let profile = request.profile.as_ref().unwrap_or(&self.profile);
+        self.post_form(
+            profile,
+            "audio/transcriptions",
+            form,
+            audio,
+            &request.headers,
+            on_chunk,
+        )
+        .await
+    }
+
+    async fn send_translation_request(
+        &self,
+        request: TranslationRequest,
+    ) -> Result<Transcription, SttError> {
+        check_file_size(&request.file)?;
+
+        self.check_model(None, &request.model, request.response_format.as_ref(), |model| {
+            (!matches!(model, Model::Whisper1)).then(|| {
+                format!(
+                    "{} does not support translations, only whisper-1 does",
+                    model.as_str()
+                )
+            })
+        })?;
+
+        let format = request.response_format.clone().unwrap_or(ResponseFormat::Json);
+
+        let audio = self.audio_charge(&request.file);
+        let mut form = MultipartFormData::new();
+        let (file_name, content_type) = prepare_audio_file(&request.file, &request.file_name)?;
+        form.add_file("file", file_name, content_type, request.file);
+        form.add_text("model", request.model.as_str());
+
+        if let Some(prompt) = request.prompt {
+            form.add_text("prompt", prompt);
+        }
+
+        if let Some(format) = request.response_format {
+            form.add_text("response_format", format.as_str());
+        }
+
+        if let Some(temperature) = request.temperature {
+            form.add_text("temperature", temperature.to_string());
+        }
+
+        let response = self
+            .post_form(
+                &self.profile,
+                "audio/translations",
+                form,
+                audio,
+                &request.headers,
+                None,
+            )
+            .await?;
+        self.finish_transcription(response, &format)
+    }
+
+    /// Measures `file` for the rate limiter's audio budget, if it has one.
+    fn audio_charge(&self, file: &[u8]) -> AudioCharge {
+        match &self.rate_limiter {
+            Some(limiter) if limiter.limits_audio() => match splitting::audio_duration(file) {
+                Some(seconds) => AudioCharge::Measured(seconds),
+                None => AudioCharge::Estimated(splitting::estimate_audio_duration(file)),
+            },
+            _ => AudioCharge::None,
+        }
+    }
+
+    /// Replaces the estimate charged for unmeasured audio with the duration
+    /// the server reported, when it reported one.
+    fn correct_audio_estimate(&self, audio: AudioCharge, reported: Option<f64>) {
+        if let (Some(limiter), AudioCharge::Estimated(estimate), Some(reported)) =
+            (&self.rate_limiter, audio, reported)
+        {
+            limiter.charge_audio(reported - estimate);
+        }
+    }
+
+    fn finish_transcription(
+        &self,
+        response: FormResponse,
+        format: &ResponseFormat,
+    ) -> Result<Transcription, SttError> {
+        let audio = response.audio;
+        let transcription = response.into_transcription(format)?;
+        let reported = match &transcription {
+            Transcription::Verbose(response) => Some(response.duration),
+            _ => transcription.usage().and_then(|usage| usage.seconds),
+        };
+        self.correct_audio_estimate(audio, reported);
+        Ok(transcription)
+    }
+
+    /// Posts a multipart form to the audio `route` of `profile` and returns the
+    /// successful response. `audio` is charged against the rate limiter's
+    /// audio budget before sending. With `on_chunk`, the body of a
+    /// successful response is handed to it as it arrives instead of being
+    /// returned.
+    async fn post_form(
+        &self,
+        profile: &ApiProfile,
+        route: &str,
+        form: MultipartFormData,
+        audio: AudioCharge,
+        request_headers: &CustomHeaders,
+        on_chunk: Option<&mut BodySink<'_>>,
+    ) -> Result<FormResponse, SttError> {
+        if profile.auth.is_missing() {
+            return Err(SttError::MissingApiKey);
+        }
+
         let (body, content_type) = form.build();
 
         // Prepare headers
         let mut headers = HashMap::new();
-        headers.insert("Authorization".to_string(), format!("Bearer {}", self.api_key));
+        profile.auth.apply_to(&mut headers);
         headers.insert("Content-Type".to_string(), content_type);
+        self.default_headers.apply_to(&mut headers);
+        request_headers.apply_to(&mut headers);
 
         // Construct URL
-        let url = url::Url::parse(&format!("{}/v1/audio/transcriptions", self.base_url))
-            .map_err(|e| SttError::HttpClient(HttpClientError::BadUrl {
-                url: e.to_string(),
-            }))?;
+        let url = profile.endpoint.url(route).map_err(|e| {
+            SttError::HttpClient(HttpClientError::BadUrl { url: e.to_string() })
+        })?;
 
         // Send request
-        let response = send_request_await_response(
-            Method::POST,
+        let http_request = HttpRequest {
+            method: Method::POST,
             url,
-            Some(headers),
-            self.timeout,
+            headers,
+            timeout: self.timeout,
             body,
-        )
-        .await
+        };
+        self.check_circuit(&profile.endpoint)?;
+        let result = self.send_form(http_request, audio, on_chunk).await;
+        self.record_outcome(&profile.endpoint, &result);
+        result
+    }
+
+    async fn send_form(
+        &self,
+        http_request: HttpRequest,
+        audio: AudioCharge,
+        on_chunk: Option<&mut BodySink<'_>>,
+    ) -> Result<FormResponse, SttError> {
+        let transport = self.transport.as_ref();
+        let policy = &self.retry_policy;
+        let throttle = self.throttle(audio.seconds());
+        let response = match on_chunk {
+            Some(on_chunk) => {
+                send_streaming_with_retry(transport, policy, throttle, http_request, on_chunk).await
+            }
+            None => send_with_retry(transport, policy, throttle, http_request).await,
+        }
         .map_err(SttError::HttpClient)?;
 
         // Handle response
-        let status = response.status();
-        let body = response.into_body();
+        let (parts, body) = response.into_parts();
+        let (status, headers) = (parts.status, parts.headers);
+        let content_type = headers
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
 
         if status.is_success() {
-            // Parse successful response
-            serde_json::from_slice(&body)
-                .map_err(|e| SttError::ParseError(e.to_string()))
+            Ok(FormResponse {
+                content_type,
+                body,
+                metadata: ResponseMetadata::from_headers(&headers),
+                audio,
+            })
         } else {
-            // Try to parse error response
-            if let Ok(error_response) = serde_json::from_slice::<ApiErrorResponse>(&body) {
-                Err(SttError::ApiError {
-                    status: status.as_u16(),
-                    message: error_response.error.message,
-                })
-            } else {
-                // Fallback to raw body text
-                let message = String::from_utf8_lossy(&body).to_string();
-                Err(SttError::ApiError {
-                    status: status.as_u16(),
-                    message,
-                })
+            Err(ApiErrorDetails::from_response(status.as_u16(), &headers, &body).into())
+        }
+    }
+
+    /// What each attempt of a request takes from the rate limiter, if any.
+    fn throttle(&self, audio_seconds: f64) -> Option<Throttle<'_>> {
+        self.rate_limiter
+            .as_deref()
+            .map(|limiter| Throttle::new(limiter, audio_seconds))
+    }
+
+    /// Fails fast while the circuit of `endpoint` is open.
+    fn check_circuit(&self, endpoint: &Endpoint) -> Result<(), SttError> {
+        match &self.circuit_breaker {
+            Some(breaker) => breaker.try_acquire(endpoint).map_err(|retry_after| {
+                SttError::CircuitOpen {
+                    endpoint: endpoint.to_string(),
+                    retry_after_ms: retry_after.as_millis() as u64,
+                }
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn record_outcome<T>(&self, endpoint: &Endpoint, result: &Result<T, SttError>) {
+        if let Some(breaker) = &self.circuit_breaker {
+            let error = result.as_ref().err().cloned().map(SpeechError::from);
+            breaker.record(endpoint, error.as_ref());
+        }
+    }
+
+    /// Sends `GET` to `route` and returns the body of a successful response.
+    async fn get(&self, route: &str) -> Result<Vec<u8>, SttError> {
+        if self.profile.auth.is_missing() {
+            return Err(SttError::MissingApiKey);
+        }
+
+        let mut headers = HashMap::new();
+        self.profile.auth.apply_to(&mut headers);
+        self.default_headers.apply_to(&mut headers);
+        let url = self.profile.endpoint.url(route).map_err(|e| {
+            SttError::HttpClient(HttpClientError::BadUrl { url: e.to_string() })
+        })?;
+
+        let http_request = HttpRequest {
+            method: Method::GET,
+            url,
+            headers,
+            timeout: self.timeout,
+            body: Vec::new(),
+        };
+        self.check_circuit(&self.profile.endpoint)?;
+        let result = self.send_get(http_request).await;
+        self.record_outcome(&self.profile.endpoint, &result);
+        result
+    }
+
+    async fn send_get(&self, http_request: HttpRequest) -> Result<Vec<u8>, SttError> {
+        let throttle = self.throttle(0.0);
+        let transport = self.transport.as_ref();
+        let response = send_with_retry(transport, &self.retry_policy, throttle, http_request)
+            .await
+            .map_err(SttError::HttpClient)?;
+
+        let (parts, body) = response.into_parts();
+        if parts.status.is_success() {
+            Ok(body)
+        } else {
+            let status = parts.status.as_u16();
+            Err(ApiErrorDetails::from_response(status, &parts.headers, &body).into())
+        }
+    }
+}
+
+/// A successful response from one of the audio endpoints.
+struct FormResponse {
+    content_type: Option<String>,
+    body: Vec<u8>,
+    metadata: ResponseMetadata,
+    audio: AudioCharge,
+}
+
+/// Seconds of uploaded audio owed to the rate limiter's audio budget.
+#[derive(Debug, Clone, Copy)]
+enum AudioCharge {
+    /// There is no audio budget, so the upload was not measured.
+    None,
+    /// Duration read from WAV or MP3 headers.
+    Measured(f64),
+    /// Duration guessed from the size of another container, corrected once
+    /// the response reports the real one.
+    Estimated(f64),
+}
+
+impl AudioCharge {
+    fn seconds(&self) -> f64 {
+        match self {
+            AudioCharge::None => 0.0,
+            AudioCharge::Measured(seconds) | AudioCharge::Estimated(seconds) => *seconds,
+        }
+    }
+}
+
+impl FormResponse {
+    fn into_transcription(self, format: &ResponseFormat) -> Result<Transcription, SttError> {
+        let mut transcription =
+            parse_transcription(format, self.content_type.as_deref(), &self.body)?;
+        *transcription.metadata_mut() = self.metadata;
+        Ok(transcription)
+    }
+}
+
+/// Decodes a streamed transcription as the transport delivers it, passing
+/// each event on as soon as it is complete.
+struct TranscriptStream<'a> {
+    on_event: &'a mut dyn FnMut(&TranscriptionStreamEvent),
+    parser: SseParser,
+    is_event_stream: bool,
+    /// The body of a server that ignored `stream`, decoded at the end.
+    buffered: Vec<u8>,
+    done: Option<TranscriptDone>,
+    /// The first decoding error; later chunks are ignored.
+    error: Option<SttError>,
+}
+
+impl<'a> TranscriptStream<'a> {
+    fn new(on_event: &'a mut dyn FnMut(&TranscriptionStreamEvent)) -> Self {
+        Self {
+            on_event,
+            parser: SseParser::new(),
+            is_event_stream: false,
+            buffered: Vec::new(),
+            done: None,
+            error: None,
+        }
+    }
+
+    fn feed(&mut self, headers: &HeaderMap, chunk: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
+        self.is_event_stream = headers
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_ascii_lowercase().starts_with("text/event-stream"))
+            .unwrap_or(false);
+        if !self.is_event_stream {
+            self.buffered.extend_from_slice(chunk);
+            return;
+        }
+        for sse_event in self.parser.feed(chunk) {
+            if let Err(error) = self.handle_event(&sse_event.data) {
+                self.error = Some(error);
+                return;
+            }
+        }
+    }
+
+    fn handle_event(&mut self, data: &str) -> Result<(), SttError> {
+        let Some(event) = decode_stream_event(data)? else {
+            return Ok(());
+        };
+        (self.on_event)(&event);
+        if let TranscriptionStreamEvent::Done(done) = event {
+            self.done = Some(done);
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, metadata: ResponseMetadata) -> Result<TranscriptDone, SttError> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        if !self.is_event_stream {
+            // The server ignored `stream`; surface the buffered result as a single event
+            let response: TranscriptionResponse = serde_json::from_slice(&self.buffered)
+                .map_err(|e| SttError::ParseError(e.to_string()))?;
+            let done = TranscriptDone {
+                text: response.text,
+                logprobs: response.logprobs,
+                usage: response.usage,
+                metadata,
+            };
+            (self.on_event)(&TranscriptionStreamEvent::Done(done.clone()));
+            return Ok(done);
+        }
+
+        for sse_event in std::mem::take(&mut self.parser).finish() {
+            self.handle_event(&sse_event.data)?;
+        }
+        let mut done = self.done.ok_or_else(|| {
+            SttError::ParseError("stream ended without transcript.text.done".to_string())
+        })?;
+        done.metadata = metadata;
+        Ok(done)
+    }
+}
+
+/// Adjusts parameters the request's model does not support: whisper-1 has no
+/// logprobs or chunking strategy, and the gpt-4o models answer in `json` or
+/// `text` only, without timestamps.
+fn adapt_to_model(request: &mut TranscriptionRequest) {
+    match request.model {
+        Model::Whisper1 => {
+            request.include.retain(|include| *include != TranscriptionInclude::Logprobs);
+            request.chunking_strategy = None;
+        }
+        Model::Gpt4oTranscribe | Model::Gpt4oMiniTranscribe => {
+            if matches!(
+                request.response_format,
+                Some(ResponseFormat::VerboseJson | ResponseFormat::Srt | ResponseFormat::Vtt)
+            ) {
+                request.response_format = Some(ResponseFormat::Json);
             }
+            request.timestamp_granularities.clear();
         }
+        Model::Custom(_) => {}
+    }
+}
+
+fn check_file_size(file: &[u8]) -> Result<(), SttError> {
+    if file.is_empty() {
+        return Err(SttError::MissingFile);
+    }
+    if file.len() > MAX_UPLOAD_BYTES {
+        return Err(SttError::FileTooLarge {
+            size: file.len(),
+            max: MAX_UPLOAD_BYTES,
+        });
+    }
+    Ok(())
+}
+
+/// Decodes a successful response body according to the requested format.
+///
+/// `text`, `srt` and `vtt` come back as plain text; the Content-Type is consulted
+/// so that a JSON body is still decoded when a server ignores the requested format.
+pub(crate) fn parse_transcription(
+    format: &ResponseFormat,
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Result<Transcription, SttError> {
+    let is_json = content_type
+        .map(|value| value.to_ascii_lowercase().contains("json"))
+        .unwrap_or(false);
+    let parse_error = |e: serde_json::Error| SttError::ParseError(e.to_string());
+
+    match format {
+        ResponseFormat::VerboseJson => serde_json::from_slice(body)
+            .map(Transcription::Verbose)
+            .map_err(parse_error),
+        ResponseFormat::Json if is_json || content_type.is_none() => {
+            serde_json::from_slice(body)
+                .map(Transcription::Json)
+                .map_err(parse_error)
+        }
+        ResponseFormat::Text
+        | ResponseFormat::Srt
+        | ResponseFormat::Vtt
+        | ResponseFormat::Custom(_)
+            if is_json =>
+        {
+            serde_json::from_slice(body)
+                .map(Transcription::Json)
+                .map_err(parse_error)
+        }
+        ResponseFormat::Json | ResponseFormat::Text | ResponseFormat::Custom(_) => {
+            body_to_string(body).map(|text| Transcription::PlainText(TextTranscription::new(text)))
+        }
+        ResponseFormat::Srt => {
+            body_to_string(body).map(|text| Transcription::Srt(TextTranscription::new(text)))
+        }
+        ResponseFormat::Vtt => {
+            body_to_string(body).map(|text| Transcription::Vtt(TextTranscription::new(text)))
+        }
+    }
+}
+
+/// Decodes the `data` payload of one SSE event, skipping event types this
+/// crate does not know about and the `[DONE]` sentinel.
+pub(crate) fn decode_stream_event(
+    data: &str,
+) -> Result<Option<TranscriptionStreamEvent>, SttError> {
+    if data.trim() == "[DONE]" {
+        return Ok(None);
+    }
+
+    let value: serde_json::Value =
+        serde_json::from_str(data).map_err(|e| SttError::ParseError(e.to_string()))?;
+    let known = value
+        .get("type")
+        .and_then(|event_type| event_type.as_str())
+        .map(|event_type| TranscriptionStreamEvent::KNOWN_TYPES.contains(&event_type))
+        .unwrap_or(false);
+    if !known {
+        return Ok(None);
     }
+
+    serde_json::from_value(value)
+        .map(Some)
+        .map_err(|e| SttError::ParseError(e.to_string()))
+}
+
+fn body_to_string(body: &[u8]) -> Result<String, SttError> {
+    String::from_utf8(body.to_vec()).map_err(|e| SttError::ParseError(e.to_string()))
 }
 
 pub struct TranscriptionRequestBuilder<'a> {
@@ -140,6 +895,18 @@ impl<'a> TranscriptionRequestBuilder<'a> {
         self
     }
 
+    /// Raw interleaved PCM laid out as `spec`, e.g. from a call or a
+    /// microphone. It is uploaded as WAV, so [`execute_long`](Self::execute_long)
+    /// can split it. Fails for a spec [`PcmSpec::validate`] rejects.
+    pub fn pcm(
+        self,
+        data: &[u8],
+        spec: PcmSpec,
+        name: impl Into<String>,
+    ) -> Result<Self, SttError> {
+        Ok(self.file(spec.to_wav(data)?, name))
+    }
+
     pub fn model(mut self, model: Model) -> Self {
         self.request.model = model;
         self
@@ -165,8 +932,152 @@ impl<'a> TranscriptionRequestBuilder<'a> {
         self
     }
 
-    pub async fn execute(self) -> Result<TranscriptionResponse, SttError> {
+    pub fn timestamp_granularities(mut self, granularities: Vec<TimestampGranularity>) -> Self {
+        self.request.timestamp_granularities = granularities;
+        self
+    }
+
+    pub fn chunking_strategy(mut self, strategy: ChunkingStrategy) -> Self {
+        self.request.chunking_strategy = Some(strategy);
+        self
+    }
+
+    /// Extra response data, e.g. [`TranscriptionInclude::Logprobs`]. Logprobs are only
+    /// available for the gpt-4o models with the `json` response format.
+    pub fn include(mut self, include: Vec<TranscriptionInclude>) -> Self {
+        self.request.include = include;
+        self
+    }
+
+    /// Sets a header on this request, replacing a client default of the same name.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.request.headers.insert(name, value);
+        self
+    }
+
+    /// Bills this request to `organization` instead of the client's.
+    pub fn organization(self, organization: impl Into<String>) -> Self {
+        self.header(ORGANIZATION_HEADER, organization)
+    }
+
+    /// Bills this request to `project` instead of the client's.
+    pub fn project(self, project: impl Into<String>) -> Self {
+        self.header(PROJECT_HEADER, project)
+    }
+
+    pub async fn execute(self) -> Result<Transcription, SttError> {
         self.client.send_transcription_request(self.request).await
     }
+
+    /// Like [`execute`](Self::execute), falling back to the targets of `policy`
+    /// when the request fails. Parameters a fallback model does not support
+    /// are adjusted, so e.g. `verbose_json` becomes `json` on gpt-4o models.
+    pub async fn execute_with_fallback(
+        self,
+        policy: &FallbackPolicy<Model>,
+    ) -> Result<FallbackResponse<Transcription>, SttError> {
+        self.client
+            .send_fallback_transcription_request(self.request, policy)
+            .await
+    }
+
+    /// Transcribes audio of any length by splitting WAV, PCM (see
+    /// [`pcm`](Self::pcm)) or MP3 input that exceeds the upload limit, using the
+    /// default [`SplitOptions`].
+    pub async fn execute_long(self) -> Result<Transcription, SttError> {
+        self.execute_long_with(SplitOptions::default()).await
+    }
+
+    /// Like [`execute_long`](Self::execute_long) with explicit split options.
+    ///
+    /// Chunks are transcribed in order, each prompted with the request's
+    /// `prompt` followed by the tail of the previous transcript, and stitched
+    /// back together. With `verbose_json`,
+    /// segment and word timestamps are relative to the original file.
+    pub async fn execute_long_with(self, options: SplitOptions) -> Result<Transcription, SttError> {
+        self.client
+            .send_long_transcription_request(self.request, options)
+            .await
+    }
+
+    /// Transcribes with `stream=true`, invoking `on_event` for every
+    /// `transcript.text.delta` and the final `transcript.text.done` event as
+    /// soon as the transport has delivered it. With the default
+    /// `HyperwareTransport`, which receives the body complete, the events
+    /// follow each other once the response has been downloaded.
+    ///
+    /// Returns the final event, which carries the full text, usage and logprobs.
+    /// Not supported by `whisper-1`.
+    pub async fn execute_stream<F>(self, mut on_event: F) -> Result<TranscriptDone, SttError>
+    where
+        F: FnMut(&TranscriptionStreamEvent),
+    {
+        self.client
+            .send_streaming_request(self.request, &mut on_event)
+            .await
+    }
+
+    /// Requests `verbose_json` and returns the transcript with segment and word timings.
+    pub async fn execute_verbose(mut self) -> Result<VerboseTranscription, SttError> {
+        self.request.response_format = Some(ResponseFormat::VerboseJson);
+        match self.client.send_transcription_request(self.request).await? {
+            Transcription::Verbose(response) => Ok(response),
+            _ => Err(SttError::ParseError("expected a verbose_json response".to_string())),
+        }
+    }
+}
+
+pub struct TranslationRequestBuilder<'a> {
+    client: &'a TranscriptionClient,
+    request: TranslationRequest,
 }
 
+impl<'a> TranslationRequestBuilder<'a> {
+    pub fn file(mut self, data: Vec<u8>, name: impl Into<String>) -> Self {
+        self.request.file = data;
+        self.request.file_name = name.into();
+        self
+    }
+
+    /// Only `whisper-1` is accepted by the translations endpoint.
+    pub fn model(mut self, model: Model) -> Self {
+        self.request.model = model;
+        self
+    }
+
+    /// Optional guidance for style; should be in English.
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.request.prompt = Some(prompt.into());
+        self
+    }
+
+    pub fn response_format(mut self, format: ResponseFormat) -> Self {
+        self.request.response_format = Some(format);
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.request.temperature = Some(temperature);
+        self
+    }
+
+    /// Sets a header on this request, replacing a client default of the same name.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.request.headers.insert(name, value);
+        self
+    }
+
+    /// Bills this request to `organization` instead of the client's.
+    pub fn organization(self, organization: impl Into<String>) -> Self {
+        self.header(ORGANIZATION_HEADER, organization)
+    }
+
+    /// Bills this request to `project` instead of the client's.
+    pub fn project(self, project: impl Into<String>) -> Self {
+        self.header(PROJECT_HEADER, project)
+    }
+
+    pub async fn execute(self) -> Result<Transcription, SttError> {
+        self.client.send_translation_request(self.request).await
+    }
+}
@@ -0,0 +1,149 @@
+use crate::types::{TokenLogprob, TranscriptDone, TranscriptionResponse};
+
+/// Confidence of one whitespace-delimited word, built from its token logprobs.
+///
+/// `start` and `end` are byte offsets into the concatenated token text, which is
+/// the transcript text as returned by the API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordConfidence {
+    pub word: String,
+    pub start: usize,
+    pub end: usize,
+    /// Sum of the logprobs of the word's tokens.
+    pub logprob: f64,
+    /// Probability of the whole word, `exp(logprob)`.
+    pub confidence: f64,
+}
+
+/// A run of consecutive words whose confidence fell below a threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LowConfidenceSpan {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+    /// Confidence of the least certain word in the span.
+    pub min_confidence: f64,
+    pub word_count: usize,
+}
+
+impl TokenLogprob {
+    /// Probability of this token, `exp(logprob)`.
+    pub fn probability(&self) -> f64 {
+        self.logprob.exp()
+    }
+}
+
+/// Groups tokens into words. A token starting with whitespace begins a new word;
+/// punctuation without leading whitespace stays attached to the preceding word.
+pub fn word_confidences(logprobs: &[TokenLogprob]) -> Vec<WordConfidence> {
+    let mut words = Vec::new();
+    let mut current: Option<WordConfidence> = None;
+    let mut offset = 0;
+
+    for token in logprobs {
+        if token.token.starts_with(char::is_whitespace) {
+            words.extend(current.take());
+        }
+
+        let trimmed = token.token.trim_start();
+        let token_end = offset + token.token.len();
+        match current.as_mut() {
+            Some(word) => {
+                word.word.push_str(&token.token);
+                word.end = token_end;
+                word.logprob += token.logprob;
+            }
+            None if !trimmed.is_empty() => {
+                current = Some(WordConfidence {
+                    word: trimmed.to_string(),
+                    start: token_end - trimmed.len(),
+                    end: token_end,
+                    logprob: token.logprob,
+                    confidence: 0.0,
+                });
+            }
+            None => {}
+        }
+        offset = token_end;
+    }
+    words.extend(current);
+
+    for word in &mut words {
+        let trimmed_len = word.word.trim_end().len();
+        word.end -= word.word.len() - trimmed_len;
+        word.word.truncate(trimmed_len);
+        word.confidence = word.logprob.exp();
+    }
+    words
+}
+
+/// Geometric mean of the token probabilities, or `None` without logprobs.
+pub fn overall_confidence(logprobs: &[TokenLogprob]) -> Option<f64> {
+    if logprobs.is_empty() {
+        return None;
+    }
+    let mean = logprobs.iter().map(|token| token.logprob).sum::<f64>() / logprobs.len() as f64;
+    Some(mean.exp())
+}
+
+/// Merges adjacent words with confidence below `threshold` into spans.
+pub fn low_confidence_spans(logprobs: &[TokenLogprob], threshold: f64) -> Vec<LowConfidenceSpan> {
+    let text: String = logprobs.iter().map(|token| token.token.as_str()).collect();
+    let mut spans: Vec<LowConfidenceSpan> = Vec::new();
+    let mut previous_low = false;
+
+    for word in word_confidences(logprobs) {
+        if word.confidence >= threshold {
+            previous_low = false;
+            continue;
+        }
+
+        match spans.last_mut() {
+            Some(span) if previous_low => {
+                span.end = word.end;
+                span.text = text[span.start..span.end].to_string();
+                span.min_confidence = span.min_confidence.min(word.confidence);
+                span.word_count += 1;
+            }
+            _ => spans.push(LowConfidenceSpan {
+                text: word.word,
+                start: word.start,
+                end: word.end,
+                min_confidence: word.confidence,
+                word_count: 1,
+            }),
+        }
+        previous_low = true;
+    }
+    spans
+}
+
+impl TranscriptionResponse {
+    /// Per-word confidence; empty unless `include[]=logprobs` was requested.
+    pub fn word_confidences(&self) -> Vec<WordConfidence> {
+        word_confidences(self.logprobs.as_deref().unwrap_or_default())
+    }
+
+    pub fn confidence(&self) -> Option<f64> {
+        overall_confidence(self.logprobs.as_deref().unwrap_or_default())
+    }
+
+    pub fn low_confidence_spans(&self, threshold: f64) -> Vec<LowConfidenceSpan> {
+        low_confidence_spans(self.logprobs.as_deref().unwrap_or_default(), threshold)
+    }
+}
+
+impl TranscriptDone {
+    /// Per-word confidence; empty unless `include[]=logprobs` was requested.
+    pub fn word_confidences(&self) -> Vec<WordConfidence> {
+        word_confidences(self.logprobs.as_deref().unwrap_or_default())
+    }
+
+    pub fn confidence(&self) -> Option<f64> {
+        overall_confidence(self.logprobs.as_deref().unwrap_or_default())
+    }
+
+    pub fn low_confidence_spans(&self, threshold: f64) -> Vec<LowConfidenceSpan> {
+        low_confidence_spans(self.logprobs.as_deref().unwrap_or_default(), threshold)
+    }
+}
@@ -1,3 +1,6 @@
+use hyperware_openai_core::api_error::{ApiErrorDetails, ApiErrorKind};
+use hyperware_openai_core::retry::is_transient_client_error;
+use hyperware_openai_core::speech::{SpeechError, SpeechErrorKind};
 use hyperware_process_lib::http::client::HttpClientError;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -12,12 +15,114 @@ pub enum SttError {
     InvalidAudioFormat(String),
     #[error("API error: {status} - {message}")]
     ApiError { status: u16, message: String },
+    #[error("Authentication failed: {0}")]
+    Authentication(Box<ApiErrorDetails>),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(Box<ApiErrorDetails>),
+    #[error("Rate limited: {0}")]
+    RateLimited(Box<ApiErrorDetails>),
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(Box<ApiErrorDetails>),
+    #[error("Invalid request: {0}")]
+    InvalidRequest(Box<ApiErrorDetails>),
+    #[error("Server error: {0}")]
+    ServerError(Box<ApiErrorDetails>),
+    #[error("Request timed out: {0}")]
+    Timeout(Box<ApiErrorDetails>),
     #[error("Failed to parse response: {0}")]
     ParseError(String),
     #[error("Missing API key")]
     MissingApiKey,
+    #[error("Circuit open for {endpoint}, retry in {retry_after_ms} ms")]
+    CircuitOpen { endpoint: String, retry_after_ms: u64 },
     #[error("Failed to build multipart form: {0}")]
     MultipartError(String),
     #[error("File is required for transcription")]
     MissingFile,
-}
\ No newline at end of file
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
+    #[error("File too large: {size} bytes (max {max} bytes)")]
+    FileTooLarge { size: usize, max: usize },
+}
+
+impl SttError {
+    /// Details of a classified API error response.
+    pub fn api_details(&self) -> Option<&ApiErrorDetails> {
+        match self {
+            SttError::Authentication(details)
+            | SttError::PermissionDenied(details)
+            | SttError::RateLimited(details)
+            | SttError::QuotaExceeded(details)
+            | SttError::InvalidRequest(details)
+            | SttError::ServerError(details)
+            | SttError::Timeout(details) => Some(details.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Whether sending the same request again later may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            SttError::HttpClient(error) => is_transient_client_error(error),
+            SttError::RateLimited(_)
+            | SttError::ServerError(_)
+            | SttError::Timeout(_)
+            | SttError::CircuitOpen { .. } => true,
+            SttError::ApiError { status, .. } => *status == 409 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Whether the request, credentials or account must change before it can
+    /// succeed. Rate limiting is not counted, as it clears by itself.
+    pub fn is_client_error(&self) -> bool {
+        match self {
+            SttError::Authentication(_)
+            | SttError::PermissionDenied(_)
+            | SttError::QuotaExceeded(_)
+            | SttError::InvalidRequest(_)
+            | SttError::InvalidModel(_)
+            | SttError::InvalidAudioFormat(_)
+            | SttError::MissingApiKey
+            | SttError::MultipartError(_)
+            | SttError::MissingFile
+            | SttError::InvalidParameter(_)
+            | SttError::FileTooLarge { .. } => true,
+            SttError::ApiError { status, .. } => (400..500).contains(status) && *status != 409,
+            _ => false,
+        }
+    }
+}
+
+impl From<ApiErrorDetails> for SttError {
+    fn from(details: ApiErrorDetails) -> Self {
+        match details.kind() {
+            ApiErrorKind::Authentication => SttError::Authentication(Box::new(details)),
+            ApiErrorKind::PermissionDenied => SttError::PermissionDenied(Box::new(details)),
+            ApiErrorKind::RateLimited => SttError::RateLimited(Box::new(details)),
+            ApiErrorKind::QuotaExceeded => SttError::QuotaExceeded(Box::new(details)),
+            ApiErrorKind::InvalidRequest => SttError::InvalidRequest(Box::new(details)),
+            ApiErrorKind::ServerError => SttError::ServerError(Box::new(details)),
+            ApiErrorKind::Timeout => SttError::Timeout(Box::new(details)),
+            ApiErrorKind::Other => SttError::ApiError {
+                status: details.status,
+                message: details.message,
+            },
+        }
+    }
+}
+
+impl From<SttError> for SpeechError {
+    fn from(error: SttError) -> Self {
+        let kind = match &error {
+            SttError::Authentication(_) | SttError::PermissionDenied(_) | SttError::MissingApiKey => {
+                SpeechErrorKind::Authentication
+            }
+            SttError::RateLimited(_) => SpeechErrorKind::RateLimited,
+            _ if error.is_retryable() => SpeechErrorKind::Unavailable,
+            _ if error.is_client_error() => SpeechErrorKind::InvalidRequest,
+            _ => SpeechErrorKind::Other,
+        };
+        SpeechError::new(kind, error.to_string())
+    }
+}
@@ -1,14 +1,42 @@
 pub mod client;
+pub mod confidence;
 pub mod error;
 pub mod multipart;
+mod provider;
+pub mod splitting;
 pub mod types;
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests;
 
-pub use client::{TranscriptionClient, TranscriptionRequestBuilder};
+pub use client::{TranscriptionClient, TranscriptionRequestBuilder, TranslationRequestBuilder};
+pub use confidence::{LowConfidenceSpan, WordConfidence};
 pub use error::SttError;
+pub use hyperware_openai_core::api_error::{ApiErrorDetails, ApiErrorKind};
+pub use hyperware_openai_core::capabilities::ServerCapabilities;
+pub use hyperware_openai_core::circuit_breaker::{
+    CircuitBreaker, CircuitBreakerPolicy, CircuitState, CircuitStatus,
+};
+pub use hyperware_openai_core::endpoint::{ApiProfile, Auth, Endpoint};
+pub use hyperware_openai_core::fallback::{
+    FallbackPolicy, FallbackResponse, FallbackTarget, ServedBy,
+};
+pub use hyperware_openai_core::headers::CustomHeaders;
+pub use hyperware_openai_core::metadata::{RateLimitInfo, ResponseMetadata};
+pub use hyperware_openai_core::rate_limit::RateLimiter;
+pub use hyperware_openai_core::retry::RetryPolicy;
+pub use hyperware_openai_core::speech::{
+    SpeechError, SpeechErrorKind, SpeechToText, SpeechToTextRequest, TimedText, TimedTranscript,
+};
+pub use hyperware_openai_core::sse;
+pub use hyperware_openai_core::transport::{
+    HttpRequest, HttpTransport, HyperwareTransport, InMemoryTransport,
+};
+pub use splitting::{audio_duration, AudioChunk, PcmSpec, SplitOptions, MAX_UPLOAD_BYTES};
 pub use types::{
-    Model, ResponseFormat, TranscriptionRequest, TranscriptionResponse,
-    TokenDetails, Usage,
+    AudioContainer, ChunkingStrategy, Model, ResponseFormat, Segment, ServerVadConfig,
+    TextTranscription, TimestampGranularity, TokenLogprob, Transcription, TranscriptDelta,
+    TranscriptDone, TranscriptionInclude, TranscriptionRequest, TranscriptionResponse,
+    TranscriptionStreamEvent, TokenDetails, TranslationRequest, Usage, VerboseTranscription, Word,
 };
\ No newline at end of file
@@ -1,3 +1,7 @@
+use crate::error::SttError;
+use crate::splitting::parse_mp3_header;
+use crate::types::AudioContainer;
+use hyperware_openai_core::audio::id3v2_len;
 use rand::Rng;
 
 pub struct MultipartFormData {
@@ -12,6 +16,12 @@ struct Part {
     data: Vec<u8>,
 }
 
+impl Default for MultipartFormData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MultipartFormData {
     pub fn new() -> Self {
         let boundary = generate_boundary();
@@ -122,4 +132,66 @@ pub fn get_content_type_for_extension(filename: &str) -> &'static str {
         "webm" => "audio/webm",
         _ => "application/octet-stream",
     }
-}
\ No newline at end of file
+}
+
+/// Identifies the audio container from the first bytes of `data`.
+pub fn detect_audio_container(data: &[u8]) -> Option<AudioContainer> {
+    if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WAVE".as_slice()) {
+        return Some(AudioContainer::Wav);
+    }
+    if data.starts_with(b"OggS") {
+        return Some(AudioContainer::Ogg);
+    }
+    if data.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        // EBML; only the WebM doctype is accepted, not general Matroska
+        let header = &data[..data.len().min(64)];
+        return header
+            .windows(4)
+            .any(|window| window == b"webm")
+            .then_some(AudioContainer::WebM);
+    }
+    if data.get(4..8) == Some(b"ftyp".as_slice()) {
+        return match data.get(8..12) {
+            Some(b"M4A ") | Some(b"M4B ") => Some(AudioContainer::M4a),
+            _ => Some(AudioContainer::Mp4),
+        };
+    }
+
+    // FLAC and MPEG audio may both be preceded by an ID3v2 tag
+    let audio = &data[id3v2_len(data)..];
+    if audio.starts_with(b"fLaC") {
+        return Some(AudioContainer::Flac);
+    }
+    if parse_mp3_header(audio).is_some() {
+        return Some(AudioContainer::Mp3);
+    }
+    None
+}
+
+/// Detects the container of `data` and returns a file name whose extension
+/// matches it, along with the MIME type to upload it with.
+pub fn prepare_audio_file(
+    data: &[u8],
+    file_name: &str,
+) -> Result<(String, &'static str), SttError> {
+    let container = detect_audio_container(data).ok_or_else(|| {
+        SttError::InvalidAudioFormat(format!(
+            "{} is not flac, mp3, mp4, m4a, ogg, wav or webm audio",
+            if file_name.is_empty() { "file" } else { file_name }
+        ))
+    })?;
+
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, extension.to_lowercase()),
+        _ => (file_name, String::new()),
+    };
+    let name = if container.accepted_extensions().contains(&extension.as_str()) {
+        file_name.to_string()
+    } else if stem.is_empty() {
+        format!("audio.{}", container.extension())
+    } else {
+        format!("{}.{}", stem, container.extension())
+    };
+
+    Ok((name, container.mime_type()))
+}
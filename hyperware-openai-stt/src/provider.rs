@@ -0,0 +1,76 @@
+use crate::client::TranscriptionClient;
+use crate::types::{Model, ResponseFormat, TimestampGranularity, Transcription};
+use hyperware_openai_core::speech::{
+    SpeechFuture, SpeechToText, SpeechToTextRequest, TimedText, TimedTranscript,
+};
+
+impl SpeechToText for TranscriptionClient {
+    /// Transcribes with `whisper-1` unless another model is named, splitting
+    /// audio over the upload limit. Segment and word timings are requested
+    /// from models known to produce `verbose_json`; others answer in `json`
+    /// and the transcript has no timings.
+    fn speech_to_text(&self, request: SpeechToTextRequest) -> SpeechFuture<'_, TimedTranscript> {
+        Box::pin(async move {
+            let model = match &request.model {
+                Some(model) => model.parse()?,
+                None => Model::Whisper1,
+            };
+            let timed = self.supports_verbose_json(&model);
+
+            let mut builder = self
+                .transcribe()
+                .file(request.audio, request.file_name)
+                .model(model);
+            if let Some(language) = request.language.clone() {
+                builder = builder.language(language);
+            }
+            if let Some(prompt) = request.prompt {
+                builder = builder.prompt(prompt);
+            }
+            if timed {
+                builder = builder
+                    .response_format(ResponseFormat::VerboseJson)
+                    .timestamp_granularities(vec![
+                        TimestampGranularity::Segment,
+                        TimestampGranularity::Word,
+                    ]);
+            } else {
+                builder = builder.response_format(ResponseFormat::Json);
+            }
+
+            let transcript = match builder.execute_long().await? {
+                Transcription::Verbose(verbose) => TimedTranscript {
+                    text: verbose.text,
+                    language: Some(verbose.language),
+                    duration: Some(verbose.duration),
+                    segments: verbose
+                        .segments
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|segment| TimedText {
+                            start: segment.start,
+                            end: segment.end,
+                            text: segment.text,
+                        })
+                        .collect(),
+                    words: verbose
+                        .words
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|word| TimedText {
+                            start: word.start,
+                            end: word.end,
+                            text: word.word,
+                        })
+                        .collect(),
+                },
+                other => TimedTranscript {
+                    text: other.text().to_string(),
+                    language: request.language,
+                    ..TimedTranscript::default()
+                },
+            };
+            Ok(transcript)
+        })
+    }
+}
@@ -0,0 +1,576 @@
+use crate::error::SttError;
+use crate::multipart::detect_audio_container;
+use crate::types::{
+    AudioContainer, TextTranscription, Transcription, TranscriptionResponse, Usage,
+    VerboseTranscription,
+};
+use hyperware_openai_core::audio::{self, id3v2_len, write_wav, Wav};
+use hyperware_openai_core::metadata::ResponseMetadata;
+
+/// Upload limit of the transcription and translation endpoints.
+pub const MAX_UPLOAD_BYTES: usize = 25 * 1024 * 1024;
+
+/// Controls how oversized audio is split for long-audio transcription.
+#[derive(Debug, Clone)]
+pub struct SplitOptions {
+    /// Upper bound for each chunk, leaving headroom below [`MAX_UPLOAD_BYTES`]
+    /// for the multipart envelope.
+    pub max_chunk_bytes: usize,
+    /// Audio shared by consecutive chunks, in seconds. Only used when the
+    /// transcript carries timestamps that allow the overlap to be removed.
+    pub overlap_seconds: f64,
+    /// Characters of the previous chunk's transcript passed as `prompt`, after
+    /// the request's own prompt.
+    pub prompt_tail_chars: usize,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        Self {
+            max_chunk_bytes: 24 * 1024 * 1024,
+            overlap_seconds: 2.0,
+            prompt_tail_chars: 500,
+        }
+    }
+}
+
+/// A self-contained piece of the original audio.
+#[derive(Debug, Clone)]
+pub struct AudioChunk {
+    pub data: Vec<u8>,
+    pub file_name: String,
+    /// Offset of the chunk in the original audio, in seconds.
+    pub start: f64,
+    pub duration: f64,
+}
+
+/// Layout of interleaved PCM samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PcmSpec {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u16,
+}
+
+impl PcmSpec {
+    /// Checks for a non-zero sample rate and channel count and a bit depth of
+    /// 8, 16, 24 or 32.
+    pub fn validate(&self) -> Result<(), SttError> {
+        if self.sample_rate == 0 || self.channels == 0 {
+            return Err(SttError::InvalidAudioFormat(
+                "PCM spec needs a sample rate and at least one channel".to_string(),
+            ));
+        }
+        if !matches!(self.bits_per_sample, 8 | 16 | 24 | 32) {
+            return Err(SttError::InvalidAudioFormat(format!(
+                "PCM samples must be 8, 16, 24 or 32 bits, not {}",
+                self.bits_per_sample
+            )));
+        }
+        Ok(())
+    }
+
+    /// Wraps interleaved samples in this layout as a WAV file.
+    pub fn to_wav(&self, pcm: &[u8]) -> Result<Vec<u8>, SttError> {
+        self.validate()?;
+        Ok(write_wav(&self.fmt_chunk(), pcm))
+    }
+
+    fn block_align(&self) -> usize {
+        audio::block_align(self.channels, self.bits_per_sample) as usize
+    }
+
+    fn fmt_chunk(&self) -> Vec<u8> {
+        audio::fmt_chunk(self.sample_rate, self.channels, self.bits_per_sample)
+    }
+}
+
+fn parse_wav(data: &[u8]) -> Result<Wav<'_>, SttError> {
+    audio::parse_wav(data).map_err(|error| SttError::InvalidAudioFormat(error.to_string()))
+}
+
+/// Splits WAV or MP3 audio into chunks of at most `options.max_chunk_bytes`.
+pub fn split_audio(
+    data: &[u8],
+    file_name: &str,
+    options: &SplitOptions,
+) -> Result<Vec<AudioChunk>, SttError> {
+    match detect_audio_container(data) {
+        Some(AudioContainer::Wav) => {
+            let wav = parse_wav(data)?;
+            split_frames(
+                wav.fmt.to_vec(),
+                wav.block_align,
+                wav.sample_rate,
+                wav.samples,
+                file_name,
+                options,
+            )
+        }
+        Some(AudioContainer::Mp3) => split_mp3(data, file_name, options),
+        _ => Err(SttError::InvalidAudioFormat(format!(
+            "{} cannot be split; long-audio transcription supports WAV and MP3",
+            file_name
+        ))),
+    }
+}
+
+/// Duration of WAV or MP3 audio in seconds, or `None` for other containers.
+pub fn audio_duration(data: &[u8]) -> Option<f64> {
+    match detect_audio_container(data)? {
+        AudioContainer::Wav => {
+            Some(audio::parse_wav(data).ok()?.duration())
+        }
+        AudioContainer::Mp3 => Some(mp3_frames(data).iter().map(|frame| frame.duration).sum()),
+        _ => None,
+    }
+}
+
+/// Rough duration in seconds of audio whose container [`audio_duration`]
+/// cannot parse, from its size and a typical bitrate for the container.
+pub(crate) fn estimate_audio_duration(data: &[u8]) -> f64 {
+    let bytes_per_second = match detect_audio_container(data) {
+        // Lossless speech compresses to roughly 384 kbit/s
+        Some(AudioContainer::Flac) => 48_000.0,
+        // Opus or Vorbis at 64 kbit/s
+        Some(AudioContainer::Ogg) | Some(AudioContainer::WebM) => 8_000.0,
+        // AAC at 128 kbit/s, and anything unrecognised
+        _ => 16_000.0,
+    };
+    data.len() as f64 / bytes_per_second
+}
+
+/// Splits raw interleaved PCM into WAV-wrapped chunks.
+pub fn split_pcm(
+    pcm: &[u8],
+    spec: PcmSpec,
+    file_name: &str,
+    options: &SplitOptions,
+) -> Result<Vec<AudioChunk>, SttError> {
+    spec.validate()?;
+    split_frames(
+        spec.fmt_chunk(),
+        spec.block_align(),
+        spec.sample_rate,
+        pcm,
+        file_name,
+        options,
+    )
+}
+
+fn split_frames(
+    fmt: Vec<u8>,
+    block_align: usize,
+    sample_rate: u32,
+    samples: &[u8],
+    file_name: &str,
+    options: &SplitOptions,
+) -> Result<Vec<AudioChunk>, SttError> {
+    let header_len = 12 + 8 + fmt.len() + 8;
+    let frames_per_chunk = options.max_chunk_bytes.saturating_sub(header_len) / block_align;
+    if frames_per_chunk == 0 {
+        return Err(SttError::InvalidParameter(
+            "max_chunk_bytes is too small to hold any audio".to_string(),
+        ));
+    }
+
+    let overlap_frames = ((options.overlap_seconds.max(0.0) * sample_rate as f64) as usize)
+        .min(frames_per_chunk / 2);
+    let step = frames_per_chunk - overlap_frames;
+    let total_frames = samples.len() / block_align;
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + frames_per_chunk).min(total_frames);
+        chunks.push(AudioChunk {
+            data: write_wav(&fmt, &samples[start * block_align..end * block_align]),
+            file_name: chunk_file_name(file_name, chunks.len(), "wav"),
+            start: start as f64 / sample_rate as f64,
+            duration: (end - start) as f64 / sample_rate as f64,
+        });
+        if end >= total_frames {
+            break;
+        }
+        start += step;
+    }
+    Ok(chunks)
+}
+
+struct Mp3Frame {
+    offset: usize,
+    len: usize,
+    duration: f64,
+}
+
+fn split_mp3(
+    data: &[u8],
+    file_name: &str,
+    options: &SplitOptions,
+) -> Result<Vec<AudioChunk>, SttError> {
+    let frames = mp3_frames(data);
+    if frames.is_empty() {
+        return Err(SttError::InvalidAudioFormat(format!(
+            "{} contains no MPEG audio frames",
+            file_name
+        )));
+    }
+
+    // Start time of every frame, plus the end of the last one
+    let mut times = Vec::with_capacity(frames.len() + 1);
+    let mut elapsed = 0.0;
+    for frame in &frames {
+        times.push(elapsed);
+        elapsed += frame.duration;
+    }
+    times.push(elapsed);
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < frames.len() {
+        let mut end = start;
+        let mut size = 0;
+        while end < frames.len() && size + frames[end].len <= options.max_chunk_bytes {
+            size += frames[end].len;
+            end += 1;
+        }
+        if end == start {
+            return Err(SttError::InvalidParameter(
+                "max_chunk_bytes is smaller than a single MP3 frame".to_string(),
+            ));
+        }
+
+        let bytes = &data[frames[start].offset..frames[end - 1].offset + frames[end - 1].len];
+        chunks.push(AudioChunk {
+            data: bytes.to_vec(),
+            file_name: chunk_file_name(file_name, chunks.len(), "mp3"),
+            start: times[start],
+            duration: times[end] - times[start],
+        });
+        if end == frames.len() {
+            break;
+        }
+
+        // Back up by the overlap, but always make progress
+        let mut next = end;
+        while next > start + 1 && times[end] - times[next] < options.overlap_seconds {
+            next -= 1;
+        }
+        start = next.max(start + (end - start).div_ceil(2));
+    }
+    Ok(chunks)
+}
+
+fn mp3_frames(data: &[u8]) -> Vec<Mp3Frame> {
+    let mut pos = id3v2_len(data);
+    let mut frames = Vec::new();
+
+    while pos + 4 <= data.len() {
+        match parse_mp3_header(&data[pos..]) {
+            Some((len, duration)) if pos + len <= data.len() => {
+                frames.push(Mp3Frame {
+                    offset: pos,
+                    len,
+                    duration,
+                });
+                pos += len;
+            }
+            // Truncated final frame
+            Some(_) => break,
+            // Resynchronize on garbage between frames
+            None => pos += 1,
+        }
+    }
+    frames
+}
+
+const BITRATES_V1_L1: [u32; 15] = [
+    0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+];
+const BITRATES_V1_L2: [u32; 15] = [
+    0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+];
+const BITRATES_V1_L3: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const BITRATES_V2_L1: [u32; 15] = [
+    0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+];
+const BITRATES_V2_L23: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+const SAMPLE_RATES: [[u32; 3]; 3] = [
+    [44100, 48000, 32000],
+    [22050, 24000, 16000],
+    [11025, 12000, 8000],
+];
+
+/// Parses an MPEG audio frame header, returning the frame length in bytes and
+/// its duration in seconds.
+pub(crate) fn parse_mp3_header(header: &[u8]) -> Option<(usize, f64)> {
+    if header.len() < 4 || header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+
+    // version: 0 = MPEG 2.5, 2 = MPEG 2, 3 = MPEG 1; layer: 1 = III, 2 = II, 3 = I
+    let version = (header[1] >> 3) & 0x03;
+    let layer = (header[1] >> 1) & 0x03;
+    let bitrate_index = (header[2] >> 4) as usize;
+    let sample_rate_index = ((header[2] >> 2) & 0x03) as usize;
+    let padding = ((header[2] >> 1) & 0x01) as usize;
+    if version == 1
+        || layer == 0
+        || bitrate_index == 0
+        || bitrate_index == 15
+        || sample_rate_index == 3
+    {
+        return None;
+    }
+
+    let is_v1 = version == 3;
+    let bitrate_kbps = match (is_v1, layer) {
+        (true, 3) => BITRATES_V1_L1,
+        (true, 2) => BITRATES_V1_L2,
+        (true, _) => BITRATES_V1_L3,
+        (false, 3) => BITRATES_V2_L1,
+        (false, _) => BITRATES_V2_L23,
+    }[bitrate_index];
+    let sample_rate = SAMPLE_RATES[match version {
+        3 => 0,
+        2 => 1,
+        _ => 2,
+    }][sample_rate_index];
+
+    let bitrate = bitrate_kbps as usize * 1000;
+    let sample_rate_bytes = sample_rate as usize;
+    let (len, samples) = match layer {
+        3 => ((12 * bitrate / sample_rate_bytes + padding) * 4, 384),
+        2 => (144 * bitrate / sample_rate_bytes + padding, 1152),
+        _ if is_v1 => (144 * bitrate / sample_rate_bytes + padding, 1152),
+        _ => (72 * bitrate / sample_rate_bytes + padding, 576),
+    };
+    Some((len, samples as f64 / sample_rate as f64))
+}
+
+fn chunk_file_name(file_name: &str, index: usize, extension: &str) -> String {
+    let stem = match file_name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => file_name,
+    };
+    format!("{}-{:03}.{}", stem, index, extension)
+}
+
+/// The prompt of a chunk after the first: the caller's own prompt, e.g.
+/// vocabulary hints, followed by the tail of the previous chunk's transcript.
+pub(crate) fn chunk_prompt(prompt: Option<&str>, tail: &str) -> Option<String> {
+    match (prompt.map(str::trim).filter(|prompt| !prompt.is_empty()), tail.is_empty()) {
+        (Some(prompt), false) => Some(format!("{} {}", prompt, tail)),
+        (Some(prompt), true) => Some(prompt.to_string()),
+        (None, false) => Some(tail.to_string()),
+        (None, true) => None,
+    }
+}
+
+/// The end of `text`, at most `max_chars` characters long, starting on a word boundary.
+pub(crate) fn prompt_tail(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    let char_count = text.chars().count();
+    if char_count <= max_chars {
+        return text.to_string();
+    }
+
+    let start = text
+        .char_indices()
+        .nth(char_count - max_chars)
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+    let tail = &text[start..];
+    match tail.split_once(char::is_whitespace) {
+        Some((_, rest)) if !rest.is_empty() => rest.trim_start().to_string(),
+        _ => tail.to_string(),
+    }
+}
+
+/// Joins per-chunk results into one transcript.
+///
+/// `bounds` holds the `(start, end)` of each chunk in the original audio, as
+/// given by [`AudioChunk::start`] and [`AudioChunk::duration`]. For verbose
+/// results, timestamps are shifted by the chunk start and each overlapping
+/// region is split at its midpoint so no segment or word is kept twice.
+pub fn stitch_transcriptions(
+    bounds: &[(f64, f64)],
+    parts: Vec<Transcription>,
+) -> Transcription {
+    if parts
+        .iter()
+        .all(|part| matches!(part, Transcription::Verbose(_)))
+    {
+        let verbose = parts
+            .into_iter()
+            .filter_map(|part| match part {
+                Transcription::Verbose(verbose) => Some(verbose),
+                _ => None,
+            })
+            .collect();
+        return Transcription::Verbose(stitch_verbose(bounds, verbose));
+    }
+
+    if parts
+        .iter()
+        .all(|part| matches!(part, Transcription::Json(_)))
+    {
+        let mut text = Vec::new();
+        let mut logprobs: Option<Vec<_>> = None;
+        let mut usage = None;
+        let mut metadata = ResponseMetadata::default();
+        for part in parts {
+            if let Transcription::Json(response) = part {
+                text.push(response.text.trim().to_string());
+                if let Some(part_logprobs) = response.logprobs {
+                    logprobs.get_or_insert_with(Vec::new).extend(part_logprobs);
+                }
+                usage = merge_usage(usage, response.usage.as_ref());
+                metadata = response.metadata;
+            }
+        }
+        return Transcription::Json(TranscriptionResponse {
+            text: join_text(text),
+            logprobs,
+            usage,
+            metadata,
+        });
+    }
+
+    let metadata = parts
+        .last()
+        .map(|part| part.metadata().clone())
+        .unwrap_or_default();
+    Transcription::PlainText(TextTranscription {
+        text: join_text(
+            parts
+                .iter()
+                .map(|part| part.text().trim().to_string())
+                .collect(),
+        ),
+        metadata,
+    })
+}
+
+fn stitch_verbose(bounds: &[(f64, f64)], parts: Vec<VerboseTranscription>) -> VerboseTranscription {
+    let mut language = String::new();
+    let mut task = None;
+    let mut segments: Option<Vec<_>> = None;
+    let mut words: Option<Vec<_>> = None;
+    let mut texts = Vec::new();
+    let mut usage = None;
+    let mut metadata = ResponseMetadata::default();
+
+    for (index, part) in parts.into_iter().enumerate() {
+        let (start, end) = bounds[index];
+        // Each chunk owns its audio up to the middle of the overlap with its neighbours
+        let own_start = if index == 0 {
+            f64::MIN
+        } else {
+            (bounds[index - 1].1 + start) / 2.0
+        };
+        let own_end = match bounds.get(index + 1) {
+            Some((next_start, _)) => (end + next_start) / 2.0,
+            None => f64::MAX,
+        };
+        let owned = |from: f64, to: f64| {
+            let middle = start + (from + to) / 2.0;
+            middle >= own_start && middle < own_end
+        };
+
+        if language.is_empty() {
+            language = part.language;
+            task = part.task;
+        }
+        texts.push(part.text.trim().to_string());
+        usage = merge_usage(usage, part.usage.as_ref());
+        metadata = part.metadata;
+
+        if let Some(part_segments) = part.segments {
+            let all = segments.get_or_insert_with(Vec::new);
+            for mut segment in part_segments {
+                if owned(segment.start, segment.end) {
+                    segment.id = all.len() as u32;
+                    segment.start += start;
+                    segment.end += start;
+                    all.push(segment);
+                }
+            }
+        }
+        if let Some(part_words) = part.words {
+            let all = words.get_or_insert_with(Vec::new);
+            for mut word in part_words {
+                if owned(word.start, word.end) {
+                    word.start += start;
+                    word.end += start;
+                    all.push(word);
+                }
+            }
+        }
+    }
+
+    let text = match (&segments, &words) {
+        (Some(segments), _) => segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect::<String>()
+            .trim()
+            .to_string(),
+        (None, Some(words)) => words
+            .iter()
+            .map(|word| word.word.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        (None, None) => join_text(texts),
+    };
+
+    VerboseTranscription {
+        task,
+        language,
+        duration: bounds.last().map(|(_, end)| *end).unwrap_or(0.0),
+        text,
+        segments,
+        words,
+        usage,
+        metadata,
+    }
+}
+
+fn join_text(parts: Vec<String>) -> String {
+    parts
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn merge_usage(total: Option<Usage>, part: Option<&Usage>) -> Option<Usage> {
+    let Some(part) = part else {
+        return total;
+    };
+    let Some(mut total) = total else {
+        return Some(part.clone());
+    };
+
+    let add = |a: Option<u32>, b: Option<u32>| match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+    };
+    total.input_tokens = add(total.input_tokens, part.input_tokens);
+    total.output_tokens = add(total.output_tokens, part.output_tokens);
+    total.total_tokens = add(total.total_tokens, part.total_tokens);
+    total.seconds = match (total.seconds, part.seconds) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0.0) + b.unwrap_or(0.0)),
+    };
+    if let (Some(total_details), Some(part_details)) = (
+        total.input_token_details.as_mut(),
+        part.input_token_details.as_ref(),
+    ) {
+        total_details.text_tokens = add(total_details.text_tokens, part_details.text_tokens);
+        total_details.audio_tokens = add(total_details.audio_tokens, part_details.audio_tokens);
+    }
+    Some(total)
+}
@@ -1,7 +1,33 @@
 #[cfg(test)]
 mod tests {
-    use crate::multipart::{get_content_type_for_extension, MultipartFormData};
-    use crate::types::{Model, ResponseFormat, TranscriptionResponse};
+    use crate::client::{decode_stream_event, parse_transcription, TranscriptionClient};
+    use crate::error::SttError;
+    use hyperware_openai_core::capabilities::ServerCapabilities;
+    use hyperware_openai_core::circuit_breaker::{CircuitBreaker, CircuitBreakerPolicy, CircuitState};
+    use hyperware_openai_core::endpoint::{ApiProfile, Endpoint};
+    use hyperware_openai_core::fallback::{FallbackPolicy, FallbackTarget};
+    use hyperware_openai_core::rate_limit::RateLimiter;
+    use hyperware_openai_core::speech::{SpeechErrorKind, SpeechToText, SpeechToTextRequest};
+    use hyperware_openai_core::retry::RetryPolicy;
+    use hyperware_openai_core::transport::{
+        BodySink, HttpRequest, HttpTransport, InMemoryTransport, SleepFuture, TransportFuture,
+    };
+    use std::sync::Mutex;
+    use std::sync::Arc;
+    use crate::confidence::{low_confidence_spans, overall_confidence, word_confidences};
+    use crate::splitting::{
+        audio_duration, prompt_tail, split_audio, split_pcm, stitch_transcriptions, PcmSpec,
+        SplitOptions,
+    };
+    use crate::sse::{parse_events, SseParser};
+    use crate::multipart::{
+        detect_audio_container, get_content_type_for_extension, prepare_audio_file,
+        MultipartFormData,
+    };
+    use crate::types::{
+        AudioContainer, ChunkingStrategy, Model, ResponseFormat, ServerVadConfig, TimestampGranularity, Transcription, TranscriptionResponse,
+        TokenLogprob, TranscriptionStreamEvent, VerboseTranscription,
+    };
 
     #[test]
     fn test_content_type_detection() {
@@ -113,4 +139,1371 @@ mod tests {
         assert_eq!(usage.usage_type, None);
         assert_eq!(usage.input_token_details, None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_timestamp_granularity_serialization() {
+        assert_eq!(TimestampGranularity::Word.as_str(), "word");
+        assert_eq!(TimestampGranularity::Segment.as_str(), "segment");
+    }
+
+    #[test]
+    fn test_verbose_transcription_deserialization() {
+        let json = r#"{
+            "task": "transcribe",
+            "language": "english",
+            "duration": 8.47,
+            "text": "The beach was a popular spot.",
+            "segments": [
+                {
+                    "id": 0,
+                    "seek": 0,
+                    "start": 0.0,
+                    "end": 3.32,
+                    "text": " The beach was a popular spot.",
+                    "tokens": [50364, 440, 7534],
+                    "temperature": 0.0,
+                    "avg_logprob": -0.286,
+                    "compression_ratio": 1.236,
+                    "no_speech_prob": 0.009
+                }
+            ],
+            "words": [
+                {"word": "The", "start": 0.0, "end": 0.24},
+                {"word": "beach", "start": 0.24, "end": 0.56}
+            ],
+            "usage": {"type": "duration", "seconds": 9}
+        }"#;
+
+        let response: VerboseTranscription = serde_json::from_str(json).unwrap();
+        assert_eq!(response.language, "english");
+        assert_eq!(response.duration, 8.47);
+
+        let segments = response.segments.unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].end, 3.32);
+        assert_eq!(segments[0].tokens, vec![50364, 440, 7534]);
+        assert_eq!(segments[0].no_speech_prob, 0.009);
+
+        let words = response.words.unwrap();
+        assert_eq!(words[1].word, "beach");
+        assert_eq!(words[1].start, 0.24);
+
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.usage_type, Some("duration".to_string()));
+        assert_eq!(usage.seconds, Some(9.0));
+    }
+
+    #[test]
+    fn test_verbose_transcription_without_words() {
+        let json = r#"{
+            "language": "english",
+            "duration": 1.5,
+            "text": "Hi.",
+            "segments": []
+        }"#;
+
+        let response: VerboseTranscription = serde_json::from_str(json).unwrap();
+        assert_eq!(response.text, "Hi.");
+        assert!(response.segments.unwrap().is_empty());
+        assert!(response.words.is_none());
+    }
+
+    #[test]
+    fn test_parse_plain_text_formats() {
+        let text =
+            parse_transcription(&ResponseFormat::Text, Some("text/plain"), b"Hello there.\n")
+                .unwrap();
+        assert!(matches!(text, Transcription::PlainText(ref t) if t.text == "Hello there.\n"));
+
+        let srt_body = b"1\n00:00:00,000 --> 00:00:01,500\nHello there.\n";
+        let srt = parse_transcription(&ResponseFormat::Srt, Some("text/plain; charset=utf-8"), srt_body)
+            .unwrap();
+        assert!(matches!(srt, Transcription::Srt(_)));
+        assert!(srt.text().contains("00:00:01,500"));
+
+        let vtt =
+            parse_transcription(&ResponseFormat::Vtt, Some("text/vtt"), b"WEBVTT\n\n").unwrap();
+        assert!(matches!(vtt, Transcription::Vtt(ref t) if t.text.starts_with("WEBVTT")));
+        assert!(vtt.usage().is_none());
+    }
+
+    #[test]
+    fn test_parse_json_formats() {
+        let json_body = br#"{"text": "Hi"}"#;
+        let json =
+            parse_transcription(&ResponseFormat::Json, Some("application/json"), json_body).unwrap();
+        assert!(matches!(json, Transcription::Json(_)));
+        assert_eq!(json.text(), "Hi");
+
+        let verbose_body = br#"{"language": "english", "duration": 1.0, "text": "Hi"}"#;
+        let verbose =
+            parse_transcription(&ResponseFormat::VerboseJson, Some("application/json"), verbose_body)
+                .unwrap();
+        assert!(matches!(verbose, Transcription::Verbose(_)));
+
+        // A JSON body is still decoded when the server ignored a plain-text format
+        let fallback =
+            parse_transcription(&ResponseFormat::Text, Some("application/json"), json_body).unwrap();
+        assert!(matches!(fallback, Transcription::Json(_)));
+    }
+
+    #[test]
+    fn test_parse_json_error() {
+        let result = parse_transcription(&ResponseFormat::Json, Some("application/json"), b"not json");
+        assert!(matches!(result, Err(SttError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_sse_parser_handles_split_chunks() {
+        let mut parser = SseParser::new();
+        assert!(parser.feed(b"event: message\r\ndata: {\"a\"").is_empty());
+        let events = parser.feed(b": 1}\r\n\r\n: keep-alive\n\ndata: line1\ndata: line2\n\n");
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event.as_deref(), Some("message"));
+        assert_eq!(events[0].data, "{\"a\": 1}");
+        assert_eq!(events[1].event, None);
+        assert_eq!(events[1].data, "line1\nline2");
+
+        // A trailing event without the blank line is flushed by finish()
+        parser.feed(b"data: tail");
+        let rest = parser.finish();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].data, "tail");
+    }
+
+    #[test]
+    fn test_decode_transcription_stream() {
+        let body = concat!(
+            "data: {\"type\":\"transcript.text.delta\",\"delta\":\"Hel\",",
+            "\"logprobs\":[{\"token\":\"Hel\",\"logprob\":-0.01,\"bytes\":[72,101,108]}]}\n\n",
+            "data: {\"type\":\"transcript.text.delta\",\"delta\":\"lo\"}\n\n",
+            "data: {\"type\":\"transcript.text.future\",\"foo\":1}\n\n",
+            "data: {\"type\":\"transcript.text.done\",\"text\":\"Hello\",",
+            "\"usage\":{\"type\":\"tokens\",\"input_tokens\":7,\"output_tokens\":2,\"total_tokens\":9}}\n\n",
+            "data: [DONE]\n\n",
+        );
+
+        let events: Vec<TranscriptionStreamEvent> = parse_events(body.as_bytes())
+            .iter()
+            .filter_map(|event| decode_stream_event(&event.data).unwrap())
+            .collect();
+        assert_eq!(events.len(), 3);
+
+        match &events[0] {
+            TranscriptionStreamEvent::Delta(delta) => {
+                assert_eq!(delta.delta, "Hel");
+                let logprobs = delta.logprobs.as_ref().unwrap();
+                assert_eq!(logprobs[0].token, "Hel");
+                assert_eq!(logprobs[0].bytes, vec![72, 101, 108]);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        match &events[2] {
+            TranscriptionStreamEvent::Done(done) => {
+                assert_eq!(done.text, "Hello");
+                assert_eq!(done.usage.as_ref().unwrap().total_tokens, Some(9));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_chunking_strategy_form_fields() {
+        assert_eq!(
+            ChunkingStrategy::Auto.form_fields(),
+            vec![("chunking_strategy".to_string(), "auto".to_string())]
+        );
+
+        let vad = ChunkingStrategy::ServerVad(ServerVadConfig {
+            threshold: Some(0.6),
+            prefix_padding_ms: Some(300),
+            silence_duration_ms: None,
+        });
+        let fields = vad.form_fields();
+        assert_eq!(fields.len(), 3);
+        assert!(fields.contains(&("chunking_strategy[type]".to_string(), "server_vad".to_string())));
+        assert!(fields.contains(&("chunking_strategy[threshold]".to_string(), "0.6".to_string())));
+        assert!(fields.contains(&(
+            "chunking_strategy[prefix_padding_ms]".to_string(),
+            "300".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_chunking_strategy_validation() {
+        assert!(ChunkingStrategy::Auto.validate().is_ok());
+        assert!(ChunkingStrategy::ServerVad(ServerVadConfig::default()).validate().is_ok());
+
+        let too_high = ChunkingStrategy::ServerVad(ServerVadConfig {
+            threshold: Some(1.5),
+            ..Default::default()
+        });
+        assert!(matches!(too_high.validate(), Err(SttError::InvalidParameter(_))));
+
+        let no_silence = ChunkingStrategy::ServerVad(ServerVadConfig {
+            silence_duration_ms: Some(0),
+            ..Default::default()
+        });
+        assert!(no_silence.validate().is_err());
+    }
+
+    fn token(token: &str, logprob: f64) -> TokenLogprob {
+        TokenLogprob {
+            token: token.to_string(),
+            logprob,
+            bytes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_logprobs_response_deserialization() {
+        let json = r#"{
+            "text": "Hi there",
+            "logprobs": [
+                {"token": "Hi", "logprob": -0.05, "bytes": [72, 105]},
+                {"token": " there", "logprob": -0.2, "bytes": [32, 116, 104, 101, 114, 101]}
+            ]
+        }"#;
+
+        let response: TranscriptionResponse = serde_json::from_str(json).unwrap();
+        let logprobs = response.logprobs.as_ref().unwrap();
+        assert_eq!(logprobs.len(), 2);
+        assert_eq!(logprobs[1].token, " there");
+
+        let words = response.word_confidences();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[1].word, "there");
+        assert!((words[0].confidence - (-0.05f64).exp()).abs() < 1e-12);
+        assert!(response.confidence().is_some());
+    }
+
+    #[test]
+    fn test_word_confidences_group_tokens() {
+        let tokens = vec![
+            token("Trans", -0.1),
+            token("cribe", -0.2),
+            token(" this", -0.01),
+            token(",", -0.05),
+            token(" now", -0.3),
+        ];
+        let words = word_confidences(&tokens);
+        assert_eq!(words.len(), 3);
+        assert_eq!(words[0].word, "Transcribe");
+        assert!((words[0].logprob - -0.3).abs() < 1e-12);
+        assert_eq!(words[1].word, "this,");
+        assert_eq!((words[1].start, words[1].end), (11, 16));
+        assert_eq!(words[2].word, "now");
+
+        let overall = overall_confidence(&tokens).unwrap();
+        assert!((overall - (-0.66f64 / 5.0).exp()).abs() < 1e-12);
+        assert!(overall_confidence(&[]).is_none());
+    }
+
+    #[test]
+    fn test_low_confidence_spans_merge_adjacent_words() {
+        let tokens = vec![
+            token("The", -0.01),
+            token(" quick", -2.0),
+            token(" brown", -1.5),
+            token(" fox", -0.01),
+            token(" jumps", -3.0),
+        ];
+        let spans = low_confidence_spans(&tokens, 0.5);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "quick brown");
+        assert_eq!(spans[0].word_count, 2);
+        assert!((spans[0].min_confidence - (-2.0f64).exp()).abs() < 1e-12);
+        assert_eq!(spans[1].text, "jumps");
+        assert_eq!(&"The quick brown fox jumps"[spans[1].start..spans[1].end], "jumps");
+    }
+
+    #[tokio::test]
+    async fn test_translation_rejects_unsupported_model() {
+        let client = TranscriptionClient::new("test-key");
+
+        let result = client
+            .translate()
+            .file(b"RIFF".to_vec(), "voicemail.wav")
+            .model(Model::Gpt4oTranscribe)
+            .execute()
+            .await;
+        assert!(matches!(result, Err(SttError::InvalidModel(_))));
+
+        let result = client.translate().execute().await;
+        assert!(matches!(result, Err(SttError::MissingFile)));
+    }
+
+    fn split_options(max_chunk_bytes: usize, overlap_seconds: f64) -> SplitOptions {
+        SplitOptions {
+            max_chunk_bytes,
+            overlap_seconds,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_split_pcm_into_wav_chunks() {
+        let spec = PcmSpec {
+            sample_rate: 8000,
+            channels: 1,
+            bits_per_sample: 16,
+        };
+        // One second of audio, 16000 bytes
+        let pcm: Vec<u8> = (0..16000u32).map(|i| (i % 251) as u8).collect();
+        let chunks = split_pcm(&pcm, spec, "call.raw", &split_options(6044, 0.1)).unwrap();
+
+        assert_eq!(chunks.len(), 4);
+        for chunk in &chunks {
+            assert!(chunk.data.len() <= 6044);
+            assert!(chunk.data.starts_with(b"RIFF"));
+            assert_eq!(&chunk.data[8..12], b"WAVE");
+        }
+        assert_eq!(chunks[0].file_name, "call-000.wav");
+        assert_eq!(chunks[0].duration, 0.375);
+        // Each chunk starts 0.1s before the previous one ended
+        assert!((chunks[1].start - 0.275).abs() < 1e-9);
+        assert!((chunks[3].start + chunks[3].duration - 1.0).abs() < 1e-9);
+
+        // Re-splitting a chunk round-trips through the WAV parser
+        let again = split_audio(&chunks[0].data, "part.wav", &split_options(100_000, 0.0)).unwrap();
+        assert_eq!(again.len(), 1);
+        assert_eq!(again[0].data, chunks[0].data);
+    }
+
+    #[test]
+    fn test_split_mp3_at_frame_boundaries() {
+        // MPEG-1 Layer III, 128 kbps, 44.1 kHz: 417-byte frames of 1152 samples
+        let mut frame = vec![0xFF, 0xFB, 0x90, 0x00];
+        frame.resize(417, 0x11);
+        let mut mp3 = b"ID3\x04\x00\x00\x00\x00\x00\x05hello".to_vec();
+        for _ in 0..20 {
+            mp3.extend_from_slice(&frame);
+        }
+
+        let chunks = split_audio(&mp3, "episode.mp3", &split_options(417 * 6, 0.0)).unwrap();
+        assert_eq!(chunks.len(), 4);
+        assert!(chunks.iter().all(|chunk| chunk.data.starts_with(&[0xFF, 0xFB])));
+        assert_eq!(chunks[0].data.len(), 417 * 6);
+        assert_eq!(chunks[3].data.len(), 417 * 2);
+        let frame_duration = 1152.0 / 44100.0;
+        assert!((chunks[1].start - 6.0 * frame_duration).abs() < 1e-9);
+
+        let overlapping = split_audio(&mp3, "episode.mp3", &split_options(417 * 6, 0.05)).unwrap();
+        assert!((overlapping[1].start - 4.0 * frame_duration).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_split_rejects_unknown_format() {
+        let result = split_audio(b"OggS\x00\x02", "clip.ogg", &SplitOptions::default());
+        assert!(matches!(result, Err(SttError::InvalidAudioFormat(_))));
+    }
+
+    #[test]
+    fn test_stitch_verbose_offsets_and_deduplicates() {
+        let first = r#"{"language": "english", "duration": 10.0, "text": "One two three",
+            "segments": [
+                {"id": 0, "start": 0.0, "end": 5.0, "text": " One two", "avg_logprob": -0.1, "compression_ratio": 1.0, "no_speech_prob": 0.0},
+                {"id": 1, "start": 8.5, "end": 10.0, "text": " three", "avg_logprob": -0.1, "compression_ratio": 1.0, "no_speech_prob": 0.0}
+            ],
+            "usage": {"type": "duration", "seconds": 10}}"#;
+        let second = r#"{"language": "english", "duration": 10.0, "text": "three four",
+            "segments": [
+                {"id": 0, "start": 0.5, "end": 2.0, "text": " three", "avg_logprob": -0.1, "compression_ratio": 1.0, "no_speech_prob": 0.0},
+                {"id": 1, "start": 2.0, "end": 6.0, "text": " four", "avg_logprob": -0.1, "compression_ratio": 1.0, "no_speech_prob": 0.0}
+            ],
+            "usage": {"type": "duration", "seconds": 10}}"#;
+        let parts = vec![
+            Transcription::Verbose(serde_json::from_str(first).unwrap()),
+            Transcription::Verbose(serde_json::from_str(second).unwrap()),
+        ];
+
+        // The chunks overlap between 8s and 10s; the midpoint is 9s
+        let stitched = stitch_transcriptions(&[(0.0, 10.0), (8.0, 18.0)], parts);
+        let Transcription::Verbose(verbose) = stitched else {
+            panic!("expected a verbose transcription");
+        };
+        let segments = verbose.segments.unwrap();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(verbose.text, "One two three four");
+        assert_eq!(segments[2].id, 2);
+        assert_eq!((segments[2].start, segments[2].end), (10.0, 14.0));
+        assert_eq!(verbose.duration, 18.0);
+        assert_eq!(verbose.usage.unwrap().seconds, Some(20.0));
+    }
+
+    #[test]
+    fn test_stitch_json_joins_text() {
+        let parts = vec![
+            Transcription::Json(serde_json::from_str(r#"{"text": "Hello there."}"#).unwrap()),
+            Transcription::Json(serde_json::from_str(r#"{"text": " General Kenobi."}"#).unwrap()),
+        ];
+        let stitched = stitch_transcriptions(&[(0.0, 5.0), (5.0, 9.0)], parts);
+        assert!(matches!(stitched, Transcription::Json(_)));
+        assert_eq!(stitched.text(), "Hello there. General Kenobi.");
+    }
+
+    #[test]
+    fn test_prompt_tail_starts_on_word_boundary() {
+        assert_eq!(prompt_tail("short text", 50), "short text");
+        assert_eq!(prompt_tail("the quick brown fox", 8), "fox");
+        assert_eq!(prompt_tail("ünïcödé wörds hère", 9), "hère");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_file_rejected_before_upload() {
+        let client = TranscriptionClient::new("test-key");
+        let result = client
+            .transcribe()
+            .file(vec![0u8; crate::splitting::MAX_UPLOAD_BYTES + 1], "huge.wav")
+            .execute()
+            .await;
+        assert!(matches!(result, Err(SttError::FileTooLarge { .. })));
+    }
+
+    #[test]
+    fn test_detect_audio_container() {
+        let cases: Vec<(&[u8], AudioContainer)> = vec![
+            (b"RIFF\x24\x00\x00\x00WAVEfmt ", AudioContainer::Wav),
+            (b"fLaC\x00\x00\x00\x22", AudioContainer::Flac),
+            (b"ID3\x03\x00\x00\x00\x00\x00\x00\xFF\xFB\x90\x00", AudioContainer::Mp3),
+            (&[0xFF, 0xF3, 0x84, 0x00], AudioContainer::Mp3),
+            (b"OggS\x00\x02\x00\x00", AudioContainer::Ogg),
+            (b"\x1A\x45\xDF\xA3\x9F\x42\x82\x84webm", AudioContainer::WebM),
+            (b"\x00\x00\x00\x20ftypM4A \x00\x00\x00\x00", AudioContainer::M4a),
+            (b"\x00\x00\x00\x18ftypmp42\x00\x00\x00\x00", AudioContainer::Mp4),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(detect_audio_container(data), Some(expected));
+        }
+
+        assert_eq!(detect_audio_container(b"\x1A\x45\xDF\xA3\x9F\x42\x82\x88matroska"), None);
+        assert_eq!(detect_audio_container(b"<html>not audio</html>"), None);
+        assert_eq!(detect_audio_container(b""), None);
+    }
+
+    #[test]
+    fn test_prepare_audio_file_fixes_names() {
+        let wav = b"RIFF\x24\x00\x00\x00WAVEfmt ";
+        assert_eq!(
+            prepare_audio_file(wav, "recording").unwrap(),
+            ("recording.wav".to_string(), "audio/wav")
+        );
+        assert_eq!(
+            prepare_audio_file(wav, "audio.bin").unwrap(),
+            ("audio.wav".to_string(), "audio/wav")
+        );
+        assert_eq!(prepare_audio_file(wav, "").unwrap().0, "audio.wav");
+
+        let mp3 = [0xFF, 0xFB, 0x90, 0x00];
+        assert_eq!(
+            prepare_audio_file(&mp3, "Song.MPGA").unwrap(),
+            ("Song.MPGA".to_string(), "audio/mpeg")
+        );
+
+        let result = prepare_audio_file(b"plain text", "notes.txt");
+        assert!(matches!(result, Err(SttError::InvalidAudioFormat(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unrecognised_audio_rejected_before_upload() {
+        let client = TranscriptionClient::new("test-key");
+        let result = client
+            .transcribe()
+            .file(b"definitely not audio".to_vec(), "upload.bin")
+            .execute()
+            .await;
+        assert!(matches!(result, Err(SttError::InvalidAudioFormat(_))));
+    }
+
+    #[test]
+    fn test_wire_names_round_trip() {
+        for model in Model::KNOWN {
+            assert_eq!(&model.as_str().parse::<Model>().unwrap(), model);
+            assert_eq!(model.to_string(), model.as_str());
+        }
+        for format in ResponseFormat::KNOWN {
+            assert_eq!(&format.as_str().parse::<ResponseFormat>().unwrap(), format);
+        }
+        assert_eq!("Whisper-1".parse::<Model>().unwrap(), Model::Whisper1);
+    }
+
+    #[test]
+    fn test_custom_model_and_format() {
+        let model: Model = "gpt-5-transcribe".parse().unwrap();
+        assert_eq!(model, Model::Custom("gpt-5-transcribe".to_string()));
+        assert!(!model.is_known());
+        assert!(Model::Whisper1.is_known());
+        assert!(matches!("".parse::<Model>(), Err(SttError::InvalidModel(_))));
+
+        assert_eq!(serde_json::to_string(&Model::Gpt4oTranscribe).unwrap(), "\"gpt-4o-transcribe\"");
+        let format: ResponseFormat = serde_json::from_str("\"verbose_json\"").unwrap();
+        assert_eq!(format, ResponseFormat::VerboseJson);
+        let custom: ResponseFormat = serde_json::from_str("\"diarized_json\"").unwrap();
+        assert_eq!(custom.as_str(), "diarized_json");
+
+        // Unknown formats are decoded by Content-Type
+        let body = br#"{"text": "Hi"}"#;
+        let result = parse_transcription(&custom, Some("application/json"), body).unwrap();
+        assert!(matches!(result, Transcription::Json(_)));
+    }
+
+    const TINY_WAV: &[u8] = b"RIFF\x24\x00\x00\x00WAVEfmt \x10\x00\x00\x00";
+
+    fn mock_client() -> (TranscriptionClient, Arc<InMemoryTransport>) {
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = TranscriptionClient::new("test-key").with_transport(transport.clone());
+        (client, transport)
+    }
+
+    #[tokio::test]
+    async fn test_transcription_request_round_trip() {
+        let (client, transport) = mock_client();
+        transport.push_response(
+            200,
+            &[("content-type", "application/json")],
+            r#"{"text": "Hello, world!"}"#,
+        );
+
+        let result = client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "greeting")
+            .model(Model::Gpt4oTranscribe)
+            .language("en")
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(result.text(), "Hello, world!");
+
+        let request = transport.last_request().unwrap();
+        assert_eq!(request.url.as_str(), "https://api.openai.com/v1/audio/transcriptions");
+        assert_eq!(request.headers["Authorization"], "Bearer test-key");
+        assert!(request.headers["Content-Type"].starts_with("multipart/form-data; boundary="));
+        let body = String::from_utf8_lossy(&request.body);
+        assert!(body.contains("filename=\"greeting.wav\""));
+        assert!(body.contains("gpt-4o-transcribe"));
+        assert!(body.contains("name=\"language\"\r\n\r\nen\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_transcription_api_error_mapping() {
+        let (client, transport) = mock_client();
+        transport.push_response(
+            400,
+            &[("content-type", "application/json")],
+            r#"{"error": {"message": "Invalid file format.", "type": "invalid_request_error", "code": null, "param": "file"}}"#,
+        );
+        transport.push_response(502, &[("x-request-id", "req_502")], "Bad Gateway");
+
+        let result = client.transcribe().file(TINY_WAV.to_vec(), "a.wav").execute().await;
+        match result {
+            Err(SttError::InvalidRequest(details)) => {
+                assert_eq!(details.status, 400);
+                assert_eq!(details.message, "Invalid file format.");
+                assert_eq!(details.error_type.as_deref(), Some("invalid_request_error"));
+                assert_eq!(details.code, None);
+                assert_eq!(details.param.as_deref(), Some("file"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let error = client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .execute()
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SttError::ServerError(_)));
+        let details = error.api_details().unwrap();
+        assert_eq!(details.status, 502);
+        assert_eq!(details.message, "Bad Gateway");
+        assert_eq!(details.request_id.as_deref(), Some("req_502"));
+        assert!(error.is_retryable());
+        assert!(!error.is_client_error());
+    }
+
+    /// Logs every body chunk it delivers, so tests can see what ran in between.
+    struct LoggingTransport {
+        inner: InMemoryTransport,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpTransport for LoggingTransport {
+        fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+            self.inner.send(request)
+        }
+
+        fn send_streaming<'a>(
+            &'a self,
+            request: HttpRequest,
+            on_chunk: &'a mut BodySink<'_>,
+        ) -> TransportFuture<'a> {
+            Box::pin(async move {
+                let mut logged = |headers: &http::HeaderMap, chunk: &[u8]| {
+                    self.log.lock().unwrap().push("chunk".to_string());
+                    on_chunk(headers, chunk);
+                };
+                self.inner.send_streaming(request, &mut logged).await
+            })
+        }
+
+        fn sleep(&self, duration: std::time::Duration) -> SleepFuture<'_> {
+            self.inner.sleep(duration)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streaming_transcription_through_transport() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let transport = LoggingTransport {
+            inner: InMemoryTransport::new(),
+            log: log.clone(),
+        };
+        transport.inner.push_chunked_response(
+            200,
+            &[("content-type", "text/event-stream")],
+            [
+                "data: {\"type\":\"transcript.text.delta\",\"delta\":\"Hel\"}\n\ndata: {\"type\":\"transcript.te",
+                "xt.delta\",\"delta\":\"lo\"}\n\n",
+                "data: {\"type\":\"transcript.text.done\",\"text\":\"Hello\"}",
+            ],
+        );
+        let client = TranscriptionClient::new("test-key").with_transport(transport);
+
+        let done = client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .model(Model::Gpt4oMiniTranscribe)
+            .execute_stream(|event| {
+                let entry = match event {
+                    TranscriptionStreamEvent::Delta(delta) => format!("delta {}", delta.delta),
+                    TranscriptionStreamEvent::Done(done) => format!("done {}", done.text),
+                };
+                log.lock().unwrap().push(entry);
+            })
+            .await
+            .unwrap();
+        assert_eq!(done.text, "Hello");
+
+        // Each delta is passed on as soon as the chunk completing it arrives
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["chunk", "delta Hel", "chunk", "delta lo", "chunk", "done Hello"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_transcription_sends_stream_flag() {
+        let (client, transport) = mock_client();
+        transport.push_response(
+            200,
+            &[("content-type", "text/event-stream")],
+            "data: {\"type\":\"transcript.text.done\",\"text\":\"Hi\"}\n\n",
+        );
+        let done = client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .model(Model::Gpt4oMiniTranscribe)
+            .execute_stream(|_| {})
+            .await
+            .unwrap();
+        assert_eq!(done.text, "Hi");
+
+        let body = String::from_utf8_lossy(&transport.last_request().unwrap().body).to_string();
+        assert!(body.contains("name=\"stream\"\r\n\r\ntrue"));
+    }
+
+    #[tokio::test]
+    async fn test_transcription_retries_server_errors() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = TranscriptionClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 2,
+                ..RetryPolicy::default()
+            });
+        transport.push_response(503, &[], "upstream overloaded");
+        transport.push_response(
+            200,
+            &[("content-type", "application/json")],
+            r#"{"text": "Recovered"}"#,
+        );
+        transport.push_response(503, &[], "upstream overloaded");
+        transport.push_response(503, &[], "upstream overloaded");
+
+        let transcription = client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(transcription.text(), "Recovered");
+
+        // Attempts are exhausted on the second call and the last error surfaces
+        let result = client.transcribe().file(TINY_WAV.to_vec(), "a.wav").execute().await;
+        assert!(matches!(result, Err(SttError::ServerError(ref details)) if details.status == 503));
+        assert_eq!(transport.requests().len(), 4);
+        assert_eq!(transport.sleeps().len(), 2);
+
+        // Both attempts carry the same multipart body
+        let requests = transport.requests();
+        assert_eq!(requests[0].body, requests[1].body);
+    }
+
+    #[tokio::test]
+    async fn test_quota_and_rate_limit_are_distinguished() {
+        let (client, transport) = mock_client();
+        transport.push_response(
+            429,
+            &[("x-request-id", "req_quota")],
+            r#"{"error": {"message": "You exceeded your current quota.", "type": "insufficient_quota", "code": "insufficient_quota", "param": null}}"#,
+        );
+        transport.push_response(
+            429,
+            &[],
+            r#"{"error": {"message": "Rate limit reached", "type": "requests", "code": "rate_limit_exceeded"}}"#,
+        );
+        transport.push_response(401, &[], r#"{"error": {"message": "Incorrect API key provided", "type": "invalid_request_error", "code": "invalid_api_key"}}"#);
+
+        let quota = client.transcribe().file(TINY_WAV.to_vec(), "a.wav").execute().await.unwrap_err();
+        assert!(matches!(quota, SttError::QuotaExceeded(_)));
+        assert_eq!(quota.api_details().unwrap().request_id.as_deref(), Some("req_quota"));
+        assert!(!quota.is_retryable());
+        assert!(quota.is_client_error());
+
+        let limited = client.transcribe().file(TINY_WAV.to_vec(), "a.wav").execute().await.unwrap_err();
+        assert!(matches!(limited, SttError::RateLimited(_)));
+        assert!(limited.is_retryable());
+        assert!(!limited.is_client_error());
+
+        let auth = client.transcribe().file(TINY_WAV.to_vec(), "a.wav").execute().await.unwrap_err();
+        assert!(matches!(auth, SttError::Authentication(_)));
+        assert_eq!(auth.api_details().unwrap().code.as_deref(), Some("invalid_api_key"));
+
+        assert!(SttError::MissingFile.is_client_error());
+        assert!(!SttError::ParseError("eof".to_string()).is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_transcription_exposes_response_metadata() {
+        let (client, transport) = mock_client();
+        let headers = [
+            ("content-type", "application/json"),
+            ("x-request-id", "req_stt"),
+            ("openai-model", "gpt-4o-transcribe"),
+            ("x-ratelimit-limit-requests", "100"),
+            ("x-ratelimit-reset-requests", "600ms"),
+        ];
+        transport.push_response(200, &headers, r#"{"text": "Hello"}"#);
+        transport.push_response(200, &[("x-request-id", "req_text")], "Hello");
+
+        let result = client.transcribe().file(TINY_WAV.to_vec(), "a.wav").execute().await.unwrap();
+        let metadata = result.metadata();
+        assert_eq!(metadata.request_id.as_deref(), Some("req_stt"));
+        assert_eq!(metadata.model.as_deref(), Some("gpt-4o-transcribe"));
+        assert_eq!(metadata.rate_limit.limit_requests, Some(100));
+        assert_eq!(
+            metadata.rate_limit.reset_requests,
+            Some(std::time::Duration::from_millis(600))
+        );
+
+        let result = client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .response_format(ResponseFormat::Text)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(result.metadata().request_id.as_deref(), Some("req_text"));
+
+        transport.push_response(200, &[("x-request-id", "req_srt")], "1\n00:00:00,000 --> 00:00:01,000\nHi\n");
+        let result = client
+            .translate()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .response_format(ResponseFormat::Srt)
+            .execute()
+            .await
+            .unwrap();
+        assert!(matches!(result, Transcription::Srt(_)));
+        assert_eq!(result.metadata().request_id.as_deref(), Some("req_srt"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_delays_by_audio_seconds() {
+        let spec = PcmSpec {
+            sample_rate: 8000,
+            channels: 1,
+            bits_per_sample: 16,
+        };
+        // Two seconds of silence wrapped as WAV
+        let pcm = vec![0u8; 32000];
+        let wav = split_pcm(&pcm, spec, "call.raw", &split_options(100_000, 0.0))
+            .unwrap()
+            .remove(0)
+            .data;
+        assert_eq!(audio_duration(&wav), Some(2.0));
+        assert_eq!(audio_duration(b"not audio"), None);
+
+        let transport = Arc::new(InMemoryTransport::new());
+        let limiter = Arc::new(RateLimiter::new().with_audio_seconds_per_minute(3.0));
+        let client = TranscriptionClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_rate_limiter(limiter);
+        for _ in 0..2 {
+            transport.push_response(200, &[("content-type", "application/json")], r#"{"text": ""}"#);
+        }
+
+        client.transcribe().file(wav.clone(), "a.wav").execute().await.unwrap();
+        assert!(transport.sleeps().is_empty());
+
+        // Only one of the three audio seconds per minute is left: wait ~20s for the other
+        client.transcribe().file(wav, "a.wav").execute().await.unwrap();
+        let sleeps = transport.sleeps();
+        assert_eq!(sleeps.len(), 1);
+        assert!(sleeps[0].as_secs_f64() > 19.5 && sleeps[0].as_secs_f64() <= 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_corrects_estimated_audio_seconds() {
+        // FLAC is not parsed; 480 kB is estimated at ten seconds
+        let mut flac = b"fLaC".to_vec();
+        flac.resize(480_000, 0);
+
+        let transport = Arc::new(InMemoryTransport::new());
+        let limiter = Arc::new(RateLimiter::new().with_audio_seconds_per_minute(60.0));
+        let client = TranscriptionClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_rate_limiter(limiter);
+        for _ in 0..2 {
+            transport.push_response(
+                200,
+                &[("content-type", "application/json")],
+                r#"{"text": "", "usage": {"type": "duration", "seconds": 55}}"#,
+            );
+        }
+
+        // The server reports 55 seconds, leaving 5 of the 60 per minute
+        client.transcribe().file(flac.clone(), "a.flac").execute().await.unwrap();
+        assert!(transport.sleeps().is_empty());
+
+        // The next ten-second estimate waits ~5s for the rest
+        client.transcribe().file(flac, "a.flac").execute().await.unwrap();
+        let sleeps = transport.sleeps();
+        assert_eq!(sleeps.len(), 1);
+        assert!(sleeps[0].as_secs_f64() > 4.5 && sleeps[0].as_secs_f64() <= 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_default_and_request_headers() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = TranscriptionClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_organization("org_default")
+            .with_project("proj_default")
+            .with_header("X-Trace", "client");
+        for _ in 0..2 {
+            transport.push_response(
+                200,
+                &[("content-type", "application/json")],
+                r#"{"text": "Hi"}"#,
+            );
+        }
+
+        client.transcribe().file(TINY_WAV.to_vec(), "a.wav").execute().await.unwrap();
+        client
+            .translate()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .project("proj_billing")
+            .header("x-trace", "request")
+            .execute()
+            .await
+            .unwrap();
+
+        let requests = transport.requests();
+        let first = &requests[0].headers;
+        assert_eq!(first["OpenAI-Organization"], "org_default");
+        assert_eq!(first["OpenAI-Project"], "proj_default");
+        assert_eq!(first["X-Trace"], "client");
+        assert_eq!(first["Authorization"], "Bearer test-key");
+
+        let second = &requests[1].headers;
+        assert_eq!(second["OpenAI-Organization"], "org_default");
+        assert_eq!(second["OpenAI-Project"], "proj_billing");
+        assert_eq!(second["x-trace"], "request");
+        assert!(!second.contains_key("X-Trace"));
+    }
+
+    #[tokio::test]
+    async fn test_azure_profile_request() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = TranscriptionClient::from_profile(ApiProfile::azure_api_key(
+            "https://my-resource.openai.azure.com",
+            "whisper-prod",
+            "2024-06-01",
+            "azure-key",
+        ))
+        .with_transport(transport.clone());
+        transport.push_response(
+            200,
+            &[("content-type", "application/json")],
+            r#"{"text": "Hi"}"#,
+        );
+
+        client.translate().file(TINY_WAV.to_vec(), "a.wav").execute().await.unwrap();
+
+        let request = transport.last_request().unwrap();
+        assert_eq!(
+            request.url.as_str(),
+            "https://my-resource.openai.azure.com/openai/deployments/whisper-prod/audio/translations?api-version=2024-06-01"
+        );
+        assert_eq!(request.headers["api-key"], "azure-key");
+        assert!(!request.headers.contains_key("Authorization"));
+
+        let keyless = TranscriptionClient::from_profile(ApiProfile::azure_api_key(
+            "https://my-resource.openai.azure.com",
+            "whisper-prod",
+            "2024-06-01",
+            "",
+        ));
+        let result = keyless.transcribe().file(TINY_WAV.to_vec(), "a.wav").execute().await;
+        assert!(matches!(result, Err(SttError::MissingApiKey)));
+    }
+
+    #[tokio::test]
+    async fn test_keyless_compatible_server() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = TranscriptionClient::from_profile(
+            ApiProfile::keyless("http://whisper.local:9000").with_path_prefix("/api/v1"),
+        )
+        .with_transport(transport.clone());
+        transport.push_response(
+            200,
+            &[("content-type", "application/json")],
+            r#"{"data": [{"id": "Systran/faster-whisper-small"}]}"#,
+        );
+        transport.push_response(
+            200,
+            &[("content-type", "application/json")],
+            r#"{"text": "Hi"}"#,
+        );
+
+        let capabilities = client.probe_capabilities().await.unwrap();
+        let client = client.with_capabilities(capabilities);
+        let model: Model = "Systran/faster-whisper-small".parse().unwrap();
+
+        // OpenAI's whisper-1-only rule does not apply to compatible servers
+        client
+            .translate()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .model(model)
+            .execute()
+            .await
+            .unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests[0].method, http::Method::GET);
+        assert_eq!(requests[0].url.as_str(), "http://whisper.local:9000/api/v1/models");
+        assert_eq!(
+            requests[1].url.as_str(),
+            "http://whisper.local:9000/api/v1/audio/translations"
+        );
+        assert!(!requests[1].headers.contains_key("Authorization"));
+
+        let result = client.transcribe().file(TINY_WAV.to_vec(), "a.wav").execute().await;
+        assert!(matches!(result, Err(SttError::InvalidModel(_))));
+        assert_eq!(transport.requests().len(), 2);
+
+        let client = client.with_capabilities(ServerCapabilities {
+            models: Vec::new(),
+            voices: None,
+            formats: Some(vec!["json".to_string(), "text".to_string()]),
+        });
+        let result = client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .response_format(ResponseFormat::Srt)
+            .execute()
+            .await;
+        assert!(matches!(result, Err(SttError::InvalidParameter(_))));
+        assert_eq!(transport.requests().len(), 2);
+
+        let unchecked = TranscriptionClient::new("test-key")
+            .with_capabilities(ServerCapabilities::default());
+        let result = unchecked
+            .translate()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .model(Model::Gpt4oTranscribe)
+            .execute()
+            .await;
+        assert!(matches!(result, Err(SttError::InvalidModel(_))));
+    }
+
+    #[tokio::test]
+    async fn test_speech_to_text_trait() {
+        let (client, transport) = mock_client();
+        transport.push_response(
+            200,
+            &[("content-type", "application/json")],
+            r#"{
+                "task": "transcribe", "language": "english", "duration": 1.5, "text": "Hi there",
+                "segments": [{"id": 0, "start": 0.0, "end": 1.5, "text": "Hi there", "avg_logprob": -0.1, "compression_ratio": 1.0, "no_speech_prob": 0.0}],
+                "words": [{"word": "Hi", "start": 0.1, "end": 0.4}, {"word": "there", "start": 0.5, "end": 0.9}]
+            }"#,
+        );
+
+        let backend: &dyn SpeechToText = &client;
+        let transcript = backend
+            .speech_to_text(SpeechToTextRequest {
+                audio: TINY_WAV.to_vec(),
+                file_name: "call.wav".to_string(),
+                language: Some("en".to_string()),
+                ..SpeechToTextRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(transcript.text, "Hi there");
+        assert_eq!(transcript.duration, Some(1.5));
+        assert_eq!(transcript.segments.len(), 1);
+        assert_eq!(transcript.words[1].text, "there");
+
+        let body = String::from_utf8_lossy(&transport.last_request().unwrap().body).to_string();
+        assert!(body.contains("name=\"model\"\r\n\r\nwhisper-1\r\n"));
+        assert!(body.contains("name=\"response_format\"\r\n\r\nverbose_json\r\n"));
+        assert!(body.contains("name=\"timestamp_granularities[]\"\r\n\r\nword\r\n"));
+
+        // gpt-4o models return text only
+        transport.push_response(
+            200,
+            &[("content-type", "application/json")],
+            r#"{"text": "Hi"}"#,
+        );
+        let transcript = backend
+            .speech_to_text(SpeechToTextRequest {
+                audio: TINY_WAV.to_vec(),
+                file_name: "call.wav".to_string(),
+                model: Some("gpt-4o-transcribe".to_string()),
+                ..SpeechToTextRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(transcript.text, "Hi");
+        assert!(transcript.segments.is_empty());
+
+        // Unknown models only get verbose_json when the server lists it
+        let custom = || SpeechToTextRequest {
+            audio: TINY_WAV.to_vec(),
+            file_name: "call.wav".to_string(),
+            model: Some("gpt-5-transcribe".to_string()),
+            ..SpeechToTextRequest::default()
+        };
+        transport.push_response(
+            200,
+            &[("content-type", "application/json")],
+            r#"{"text": "Hi"}"#,
+        );
+        let transcript = backend.speech_to_text(custom()).await.unwrap();
+        assert_eq!(transcript.text, "Hi");
+        assert!(transcript.segments.is_empty());
+        let body = String::from_utf8_lossy(&transport.last_request().unwrap().body).to_string();
+        assert!(body.contains("name=\"response_format\"\r\n\r\njson\r\n"));
+        assert!(!body.contains("timestamp_granularities"));
+
+        let listed = TranscriptionClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_capabilities(ServerCapabilities {
+                models: vec!["gpt-5-transcribe".to_string()],
+                voices: None,
+                formats: Some(vec!["json".to_string(), "verbose_json".to_string()]),
+            });
+        transport.push_response(
+            200,
+            &[("content-type", "application/json")],
+            r#"{"language": "english", "duration": 1.0, "text": "Hi", "segments": []}"#,
+        );
+        let transcript = listed.speech_to_text(custom()).await.unwrap();
+        assert_eq!(transcript.duration, Some(1.0));
+        let body = String::from_utf8_lossy(&transport.last_request().unwrap().body).to_string();
+        assert!(body.contains("name=\"response_format\"\r\n\r\nverbose_json\r\n"));
+
+        transport.push_response(429, &[], r#"{"error": {"message": "Slow down"}}"#);
+        let error = backend
+            .speech_to_text(SpeechToTextRequest {
+                audio: TINY_WAV.to_vec(),
+                file_name: "call.wav".to_string(),
+                ..SpeechToTextRequest::default()
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind, SpeechErrorKind::RateLimited);
+
+        let error = backend
+            .speech_to_text(SpeechToTextRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind, SpeechErrorKind::InvalidRequest);
+    }
+
+    #[tokio::test]
+    async fn test_transcription_fallback_chain() {
+        let (client, transport) = mock_client();
+        let json = [("content-type", "application/json")];
+        transport.push_response(503, &[], r#"{"error": {"message": "Overloaded"}}"#);
+        transport.push_response(429, &[], r#"{"error": {"message": "Slow down"}}"#);
+        transport.push_response(200, &json, r#"{"text": "Hi"}"#);
+
+        let policy = FallbackPolicy::models([Model::Gpt4oMiniTranscribe, Model::Whisper1]);
+        let served = client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .model(Model::Gpt4oTranscribe)
+            .execute_with_fallback(&policy)
+            .await
+            .unwrap();
+        assert_eq!(served.response.text(), "Hi");
+        assert_eq!(served.served_by.index, 2);
+        assert_eq!(served.served_by.model, "whisper-1");
+
+        let models: Vec<String> = transport
+            .requests()
+            .iter()
+            .map(|request| {
+                let body = String::from_utf8_lossy(&request.body).to_string();
+                let marker = "name=\"model\"\r\n\r\n";
+                let start = body.find(marker).unwrap() + marker.len();
+                body[start..].split("\r\n").next().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(models, vec!["gpt-4o-transcribe", "gpt-4o-mini-transcribe", "whisper-1"]);
+
+        // Errors the policy does not cover are returned at once
+        transport.push_response(401, &[], r#"{"error": {"message": "Bad key"}}"#);
+        let result = client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .execute_with_fallback(&policy)
+            .await;
+        assert!(matches!(result, Err(SttError::Authentication(_))));
+        assert_eq!(transport.pending(), 0);
+        assert_eq!(transport.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_transcription_fallback_adapts_parameters_and_endpoint() {
+        let (client, transport) = mock_client();
+        let json = [("content-type", "application/json")];
+        transport.push_response(500, &[], r#"{"error": {"message": "Oops"}}"#);
+        transport.push_response(200, &json, r#"{"text": "Hi"}"#);
+
+        let mut backup = ApiProfile::openai("k");
+        backup.endpoint.set_base_url("https://backup.example.com");
+        let policy = FallbackPolicy::new(vec![FallbackTarget::new(
+            backup.clone(),
+            Model::Gpt4oMiniTranscribe,
+        )]);
+        let served = client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .response_format(ResponseFormat::VerboseJson)
+            .timestamp_granularities(vec![TimestampGranularity::Word])
+            .execute_with_fallback(&policy)
+            .await
+            .unwrap();
+        assert!(matches!(served.response, Transcription::Json(_)));
+        assert_eq!(served.served_by.endpoint, backup.endpoint);
+        assert!(matches!(served.served_by.endpoint, Endpoint::OpenAi { .. }));
+
+        let request = transport.last_request().unwrap();
+        assert_eq!(request.url.host_str(), Some("backup.example.com"));
+        assert_eq!(request.headers["Authorization"], "Bearer k");
+        let body = String::from_utf8_lossy(&request.body).to_string();
+        assert!(body.contains("name=\"response_format\"\r\n\r\njson\r\n"));
+        assert!(!body.contains("timestamp_granularities"));
+    }
+
+    #[tokio::test]
+    async fn test_azure_skips_openai_model_rules() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let azure = ApiProfile::azure_api_key(
+            "https://r.openai.azure.com",
+            "gpt-4o transcribe",
+            "2024-06-01",
+            "k",
+        );
+        let client = TranscriptionClient::from_profile(azure.clone())
+            .with_transport(transport.clone());
+        let json = [("content-type", "application/json")];
+        transport.push_response(200, &json, r#"{"text": "Hi"}"#);
+
+        // The deployment picks the model, so any model name may translate
+        client
+            .translate()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .model(Model::Gpt4oTranscribe)
+            .execute()
+            .await
+            .unwrap();
+        let request = transport.last_request().unwrap();
+        assert_eq!(
+            request.url.path(),
+            "/openai/deployments/gpt-4o%20transcribe/audio/translations"
+        );
+
+        // Fallback targets on Azure keep the request as it was
+        transport.push_response(503, &[], "upstream overloaded");
+        transport.push_response(200, &json, r#"{"text": "Hi", "language": "english", "duration": 1.0}"#);
+        let policy = FallbackPolicy::new(vec![FallbackTarget::new(
+            azure,
+            Model::Gpt4oMiniTranscribe,
+        )]);
+        client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .response_format(ResponseFormat::VerboseJson)
+            .execute_with_fallback(&policy)
+            .await
+            .unwrap();
+        let body = String::from_utf8_lossy(&transport.last_request().unwrap().body).to_string();
+        assert!(body.contains("name=\"response_format\"\r\n\r\nverbose_json\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_capabilities_uses_circuit_breaker() {
+        let breaker = Arc::new(CircuitBreaker::new(CircuitBreakerPolicy {
+            minimum_requests: 2,
+            ..CircuitBreakerPolicy::default()
+        }));
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = TranscriptionClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_circuit_breaker(breaker.clone());
+        transport.push_response(503, &[], "upstream overloaded");
+        transport.push_response(503, &[], "upstream overloaded");
+
+        // Failed probes count against the circuit like any other request
+        for _ in 0..2 {
+            let result = client.probe_capabilities().await;
+            assert!(matches!(result, Err(SttError::ServerError(_))));
+        }
+        assert_eq!(client.circuit_status().unwrap().state, CircuitState::Open);
+
+        let result = client.probe_capabilities().await;
+        assert!(matches!(result, Err(SttError::CircuitOpen { .. })));
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast() {
+        let breaker = Arc::new(CircuitBreaker::new(CircuitBreakerPolicy {
+            minimum_requests: 2,
+            ..CircuitBreakerPolicy::default()
+        }));
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = TranscriptionClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_circuit_breaker(breaker.clone());
+        transport.push_response(503, &[], "upstream overloaded");
+        transport.push_response(500, &[], "upstream failed");
+        for _ in 0..2 {
+            let result = client.transcribe().file(TINY_WAV.to_vec(), "a.wav").execute().await;
+            assert!(matches!(result, Err(SttError::ServerError(_))));
+        }
+
+        let status = client.circuit_status().unwrap();
+        assert_eq!(status.state, CircuitState::Open);
+        let result = client.transcribe().file(TINY_WAV.to_vec(), "a.wav").execute().await;
+        match result {
+            Err(error @ SttError::CircuitOpen { .. }) => {
+                assert!(error.is_retryable());
+                assert!(error.to_string().contains("https://api.openai.com/v1"));
+            }
+            other => panic!("expected an open circuit, got {:?}", other),
+        }
+        assert_eq!(transport.requests().len(), 2);
+
+        // An open circuit falls back to the next endpoint
+        transport.push_response(200, &[("content-type", "application/json")], r#"{"text": "Hi"}"#);
+        let policy = FallbackPolicy::new(vec![FallbackTarget::new(
+            ApiProfile::keyless("http://localhost:8000"),
+            Model::Whisper1,
+        )]);
+        let served = client
+            .transcribe()
+            .file(TINY_WAV.to_vec(), "a.wav")
+            .execute_with_fallback(&policy)
+            .await
+            .unwrap();
+        assert_eq!(served.served_by.index, 1);
+        assert_eq!(transport.requests().len(), 3);
+        assert_eq!(breaker.statuses().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_long_pcm_transcription_keeps_prompt() {
+        let (client, transport) = mock_client();
+        let json = [("content-type", "application/json")];
+        transport.push_response(200, &json, r#"{"text": "Call the Zyx team"}"#);
+        transport.push_response(200, &json, r#"{"text": "tomorrow."}"#);
+
+        let spec = PcmSpec {
+            sample_rate: 8000,
+            channels: 1,
+            bits_per_sample: 16,
+        };
+        let result = client
+            .transcribe()
+            .pcm(&vec![0u8; 16000], spec, "call.wav")
+            .unwrap()
+            .prompt("Zyx, Qorp")
+            .execute_long_with(split_options(10_000, 0.0))
+            .await
+            .unwrap();
+        assert_eq!(result.text(), "Call the Zyx team tomorrow.");
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        let prompt = |index: usize| {
+            let body = String::from_utf8_lossy(&requests[index].body).to_string();
+            let marker = "name=\"prompt\"\r\n\r\n";
+            let start = body.find(marker).unwrap() + marker.len();
+            body[start..].split("\r\n").next().unwrap().to_string()
+        };
+        assert_eq!(prompt(0), "Zyx, Qorp");
+        assert_eq!(prompt(1), "Zyx, Qorp Call the Zyx team");
+        assert!(requests[0].body.windows(4).any(|window| window == b"RIFF"));
+    }
+
+    #[test]
+    fn test_pcm_spec_validation() {
+        let spec = |sample_rate, channels, bits_per_sample| PcmSpec {
+            sample_rate,
+            channels,
+            bits_per_sample,
+        };
+        let invalid_specs = [
+            spec(0, 1, 16),
+            spec(8000, 0, 16),
+            spec(8000, 1, 0),
+            spec(8000, 1, 4),
+            spec(8000, 2, 12),
+        ];
+        for invalid in invalid_specs {
+            assert!(invalid.validate().is_err(), "{:?}", invalid);
+            assert!(invalid.to_wav(&[0; 4]).is_err());
+            let result = split_pcm(&[0; 4], invalid, "a.raw", &split_options(10_000, 0.0));
+            assert!(matches!(result, Err(SttError::InvalidAudioFormat(_))));
+        }
+
+        let (client, _) = mock_client();
+        let result = client.transcribe().pcm(&[0; 4], spec(8000, 1, 20), "a.raw");
+        assert!(matches!(result, Err(SttError::InvalidAudioFormat(_))));
+
+        // 24-bit stereo: six bytes per frame in both the header and the chunking
+        let stereo = spec(48_000, 2, 24);
+        let wav = stereo.to_wav(&[0; 6 * 48_000]).unwrap();
+        assert_eq!(&wav[32..34], &6u16.to_le_bytes());
+        assert_eq!(audio_duration(&wav), Some(1.0));
+        let chunks =
+            split_pcm(&[0; 6 * 48_000], stereo, "a.raw", &split_options(100_000, 0.0)).unwrap();
+        assert!(chunks.iter().all(|chunk| (chunk.data.len() - 44) % 6 == 0));
+    }
+}
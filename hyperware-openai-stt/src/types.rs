@@ -1,38 +1,78 @@
+use crate::error::SttError;
+use hyperware_openai_core::endpoint::ApiProfile;
+use hyperware_openai_core::headers::CustomHeaders;
+use hyperware_openai_core::metadata::ResponseMetadata;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
+/// Transcription model. Unknown ids are kept in `Custom` so new models can be
+/// used without a crate release.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Model {
-    #[serde(rename = "gpt-4o-transcribe")]
     Gpt4oTranscribe,
-    #[serde(rename = "gpt-4o-mini-transcribe")]
     Gpt4oMiniTranscribe,
-    #[serde(rename = "whisper-1")]
     Whisper1,
+    Custom(String),
 }
 
 impl Model {
+    pub const KNOWN: &'static [Model] = &[
+        Model::Gpt4oTranscribe,
+        Model::Gpt4oMiniTranscribe,
+        Model::Whisper1,
+    ];
+
     pub fn as_str(&self) -> &str {
         match self {
             Model::Gpt4oTranscribe => "gpt-4o-transcribe",
             Model::Gpt4oMiniTranscribe => "gpt-4o-mini-transcribe",
             Model::Whisper1 => "whisper-1",
+            Model::Custom(name) => name,
         }
     }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Model::Custom(_))
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+impl FromStr for Model {
+    type Err = SttError;
+
+    /// Known ids match case-insensitively; anything else becomes `Custom`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(SttError::InvalidModel("model name is empty".to_string()));
+        }
+        Ok(Model::KNOWN
+            .iter()
+            .find(|model| model.as_str().eq_ignore_ascii_case(s))
+            .cloned()
+            .unwrap_or_else(|| Model::Custom(s.to_string())))
+    }
+}
+
+/// Output format of a transcription. Unknown formats are kept in `Custom`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ResponseFormat {
     Json,
     Text,
     Srt,
-    #[serde(rename = "verbose_json")]
     VerboseJson,
     Vtt,
+    Custom(String),
 }
 
 impl ResponseFormat {
+    pub const KNOWN: &'static [ResponseFormat] = &[
+        ResponseFormat::Json,
+        ResponseFormat::Text,
+        ResponseFormat::Srt,
+        ResponseFormat::VerboseJson,
+        ResponseFormat::Vtt,
+    ];
+
     pub fn as_str(&self) -> &str {
         match self {
             ResponseFormat::Json => "json",
@@ -40,6 +80,217 @@ impl ResponseFormat {
             ResponseFormat::Srt => "srt",
             ResponseFormat::VerboseJson => "verbose_json",
             ResponseFormat::Vtt => "vtt",
+            ResponseFormat::Custom(name) => name,
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, ResponseFormat::Custom(_))
+    }
+}
+
+impl FromStr for ResponseFormat {
+    type Err = SttError;
+
+    /// Known formats match case-insensitively; anything else becomes `Custom`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(SttError::InvalidParameter("response format is empty".to_string()));
+        }
+        Ok(ResponseFormat::KNOWN
+            .iter()
+            .find(|format| format.as_str().eq_ignore_ascii_case(s))
+            .cloned()
+            .unwrap_or_else(|| ResponseFormat::Custom(s.to_string())))
+    }
+}
+
+hyperware_openai_core::impl_wire_name!(Model, ResponseFormat);
+
+/// Audio container recognised from the leading bytes of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioContainer {
+    Wav,
+    Flac,
+    Mp3,
+    Ogg,
+    WebM,
+    Mp4,
+    M4a,
+}
+
+impl AudioContainer {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            AudioContainer::Wav => "audio/wav",
+            AudioContainer::Flac => "audio/flac",
+            AudioContainer::Mp3 => "audio/mpeg",
+            AudioContainer::Ogg => "audio/ogg",
+            AudioContainer::WebM => "audio/webm",
+            AudioContainer::Mp4 => "audio/mp4",
+            AudioContainer::M4a => "audio/m4a",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioContainer::Wav => "wav",
+            AudioContainer::Flac => "flac",
+            AudioContainer::Mp3 => "mp3",
+            AudioContainer::Ogg => "ogg",
+            AudioContainer::WebM => "webm",
+            AudioContainer::Mp4 => "mp4",
+            AudioContainer::M4a => "m4a",
+        }
+    }
+
+    /// File extensions the API accepts for this container.
+    pub fn accepted_extensions(&self) -> &'static [&'static str] {
+        match self {
+            AudioContainer::Wav => &["wav"],
+            AudioContainer::Flac => &["flac"],
+            AudioContainer::Mp3 => &["mp3", "mpeg", "mpga"],
+            AudioContainer::Ogg => &["ogg"],
+            AudioContainer::WebM => &["webm"],
+            AudioContainer::Mp4 | AudioContainer::M4a => &["mp4", "m4a"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampGranularity {
+    Word,
+    Segment,
+}
+
+impl TimestampGranularity {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TimestampGranularity::Word => "word",
+            TimestampGranularity::Segment => "segment",
+        }
+    }
+}
+
+/// How the server cuts the audio into chunks before transcribing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChunkingStrategy {
+    /// Loudness normalization followed by server-chosen VAD boundaries.
+    Auto,
+    /// Voice activity detection with manually tuned parameters.
+    ServerVad(ServerVadConfig),
+}
+
+/// Tuning for `server_vad` chunking. Unset fields use the server defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServerVadConfig {
+    /// Sensitivity between 0.0 and 1.0; higher requires louder audio to count as speech.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
+    /// Audio to include before detected speech, in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix_padding_ms: Option<u32>,
+    /// Silence that ends a chunk, in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub silence_duration_ms: Option<u32>,
+}
+
+impl ChunkingStrategy {
+    pub fn validate(&self) -> Result<(), SttError> {
+        let ChunkingStrategy::ServerVad(config) = self else {
+            return Ok(());
+        };
+
+        if let Some(threshold) = config.threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(SttError::InvalidParameter(format!(
+                    "chunking_strategy threshold must be between 0.0 and 1.0, got {}",
+                    threshold
+                )));
+            }
+        }
+
+        if config.silence_duration_ms == Some(0) {
+            return Err(SttError::InvalidParameter(
+                "chunking_strategy silence_duration_ms must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Multipart fields for this strategy, using the bracketed object notation
+    /// the API expects (`chunking_strategy[type]=server_vad`, ...).
+    pub fn form_fields(&self) -> Vec<(String, String)> {
+        match self {
+            ChunkingStrategy::Auto => vec![("chunking_strategy".to_string(), "auto".to_string())],
+            ChunkingStrategy::ServerVad(config) => {
+                let mut fields = vec![(
+                    "chunking_strategy[type]".to_string(),
+                    "server_vad".to_string(),
+                )];
+                if let Some(threshold) = config.threshold {
+                    fields.push(("chunking_strategy[threshold]".to_string(), threshold.to_string()));
+                }
+                if let Some(padding) = config.prefix_padding_ms {
+                    fields.push((
+                        "chunking_strategy[prefix_padding_ms]".to_string(),
+                        padding.to_string(),
+                    ));
+                }
+                if let Some(silence) = config.silence_duration_ms {
+                    fields.push((
+                        "chunking_strategy[silence_duration_ms]".to_string(),
+                        silence.to_string(),
+                    ));
+                }
+                fields
+            }
+        }
+    }
+}
+
+/// Request for `/v1/audio/translations`, which always produces English text.
+#[derive(Debug, Clone)]
+pub struct TranslationRequest {
+    pub file: Vec<u8>,
+    pub file_name: String,
+    pub model: Model,
+    pub prompt: Option<String>,
+    pub response_format: Option<ResponseFormat>,
+    pub temperature: Option<f32>,
+    /// Extra headers, replacing client defaults of the same name.
+    pub headers: CustomHeaders,
+}
+
+impl Default for TranslationRequest {
+    fn default() -> Self {
+        Self {
+            file: Vec::new(),
+            file_name: String::new(),
+            model: Model::Whisper1,
+            prompt: None,
+            response_format: None,
+            temperature: None,
+            headers: CustomHeaders::new(),
+        }
+    }
+}
+
+/// Extra data to include in the transcription response (`include[]`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptionInclude {
+    Logprobs,
+}
+
+impl TranscriptionInclude {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TranscriptionInclude::Logprobs => "logprobs",
         }
     }
 }
@@ -53,6 +304,14 @@ pub struct TranscriptionRequest {
     pub prompt: Option<String>,
     pub response_format: Option<ResponseFormat>,
     pub temperature: Option<f32>,
+    pub timestamp_granularities: Vec<TimestampGranularity>,
+    pub stream: bool,
+    pub chunking_strategy: Option<ChunkingStrategy>,
+    pub include: Vec<TranscriptionInclude>,
+    /// Extra headers, replacing client defaults of the same name.
+    pub headers: CustomHeaders,
+    /// Endpoint and credentials for this request; the client's when `None`.
+    pub profile: Option<ApiProfile>,
 }
 
 impl Default for TranscriptionRequest {
@@ -65,6 +324,12 @@ impl Default for TranscriptionRequest {
             prompt: None,
             response_format: None,
             temperature: None,
+            timestamp_granularities: Vec::new(),
+            stream: false,
+            chunking_strategy: None,
+            include: Vec::new(),
+            headers: CustomHeaders::new(),
+            profile: None,
         }
     }
 }
@@ -72,8 +337,178 @@ impl Default for TranscriptionRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptionResponse {
     pub text: String,
+    /// Per-token log probabilities, present when `include[]=logprobs` was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<Vec<TokenLogprob>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+    /// Headers of the HTTP response; not part of the JSON body.
+    #[serde(skip)]
+    pub metadata: ResponseMetadata,
+}
+
+/// Result of a `text`, `srt` or `vtt` transcription.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextTranscription {
+    /// The transcript, or for `srt` and `vtt` the full subtitle document.
+    pub text: String,
+    /// Headers of the HTTP response.
+    pub metadata: ResponseMetadata,
+}
+
+impl TextTranscription {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            metadata: ResponseMetadata::default(),
+        }
+    }
+}
+
+/// Transcription result, shaped by the requested [`ResponseFormat`].
+#[derive(Debug, Clone)]
+pub enum Transcription {
+    Json(TranscriptionResponse),
+    Verbose(VerboseTranscription),
+    PlainText(TextTranscription),
+    Srt(TextTranscription),
+    Vtt(TextTranscription),
+}
+
+impl Transcription {
+    /// The transcript text. For `Srt` and `Vtt` this is the full subtitle document.
+    pub fn text(&self) -> &str {
+        match self {
+            Transcription::Json(response) => &response.text,
+            Transcription::Verbose(response) => &response.text,
+            Transcription::PlainText(response)
+            | Transcription::Srt(response)
+            | Transcription::Vtt(response) => &response.text,
+        }
+    }
+
+    pub fn usage(&self) -> Option<&Usage> {
+        match self {
+            Transcription::Json(response) => response.usage.as_ref(),
+            Transcription::Verbose(response) => response.usage.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Response headers such as the request id, whatever the format.
+    pub fn metadata(&self) -> &ResponseMetadata {
+        match self {
+            Transcription::Json(response) => &response.metadata,
+            Transcription::Verbose(response) => &response.metadata,
+            Transcription::PlainText(response)
+            | Transcription::Srt(response)
+            | Transcription::Vtt(response) => &response.metadata,
+        }
+    }
+
+    pub(crate) fn metadata_mut(&mut self) -> &mut ResponseMetadata {
+        match self {
+            Transcription::Json(response) => &mut response.metadata,
+            Transcription::Verbose(response) => &mut response.metadata,
+            Transcription::PlainText(response)
+            | Transcription::Srt(response)
+            | Transcription::Vtt(response) => &mut response.metadata,
+        }
+    }
+}
+
+/// Result of a `verbose_json` transcription: the text plus timing information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerboseTranscription {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    pub language: String,
+    /// Duration of the input audio in seconds.
+    pub duration: f64,
+    pub text: String,
+    /// Present when segment timestamps were requested (the API default).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segments: Option<Vec<Segment>>,
+    /// Present only when word timestamps were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<Word>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage: Option<Usage>,
+    /// Headers of the HTTP response; not part of the JSON body.
+    #[serde(skip)]
+    pub metadata: ResponseMetadata,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Segment {
+    pub id: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seek: Option<u32>,
+    /// Start time of the segment in seconds.
+    pub start: f64,
+    /// End time of the segment in seconds.
+    pub end: f64,
+    pub text: String,
+    #[serde(default)]
+    pub tokens: Vec<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    pub avg_logprob: f64,
+    pub compression_ratio: f64,
+    pub no_speech_prob: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Word {
+    pub word: String,
+    /// Start time of the word in seconds.
+    pub start: f64,
+    /// End time of the word in seconds.
+    pub end: f64,
+}
+
+/// Log probability of a single output token.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenLogprob {
+    pub token: String,
+    pub logprob: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bytes: Vec<u8>,
+}
+
+/// Event emitted by a streaming (`stream=true`) transcription.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[allow(clippy::large_enum_variant)] // Events are short-lived; boxing would only add noise for callers
+pub enum TranscriptionStreamEvent {
+    #[serde(rename = "transcript.text.delta")]
+    Delta(TranscriptDelta),
+    #[serde(rename = "transcript.text.done")]
+    Done(TranscriptDone),
+}
+
+impl TranscriptionStreamEvent {
+    pub(crate) const KNOWN_TYPES: [&'static str; 2] =
+        ["transcript.text.delta", "transcript.text.done"];
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptDelta {
+    pub delta: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<Vec<TokenLogprob>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptDone {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<Vec<TokenLogprob>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+    /// Headers of the HTTP response; not part of the JSON body.
+    #[serde(skip)]
+    pub metadata: ResponseMetadata,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -88,6 +523,9 @@ pub struct Usage {
     pub output_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total_tokens: Option<u32>,
+    /// Billed audio duration, reported by `whisper-1` instead of token counts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seconds: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -96,17 +534,4 @@ pub struct TokenDetails {
     pub text_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub audio_tokens: Option<u32>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ApiErrorResponse {
-    pub error: ApiErrorDetail,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ApiErrorDetail {
-    pub message: String,
-    #[serde(rename = "type")]
-    pub error_type: Option<String>,
-    pub code: Option<String>,
 }
\ No newline at end of file
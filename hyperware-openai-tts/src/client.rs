@@ -1,34 +1,68 @@
 use crate::error::TtsError;
+use crate::pcm::PcmBuffer;
+use crate::splitting;
+use crate::telephony::{TelephonyAudio, TelephonyOptions};
 use crate::types::{
-    ApiErrorResponse, AudioFormat, SpeechRequest, SpeechRequestJson, SpeechResponse, TtsModel,
-    Voice,
+    AudioFormat, SpeechRequest, SpeechRequestJson, SpeechResponse, SpeechStreamEvent,
+    SpeechUsage, StreamFormat, TtsModel, Voice,
 };
-use hyperware_process_lib::http::client::send_request_await_response;
+use hyperware_openai_core::api_error::ApiErrorDetails;
+use hyperware_openai_core::capabilities::{ServerCapabilities, MODELS_ROUTE, VOICES_ROUTE};
+use hyperware_openai_core::circuit_breaker::{CircuitBreaker, CircuitStatus};
+use hyperware_openai_core::endpoint::{ApiProfile, Endpoint};
+use hyperware_openai_core::fallback::{FallbackPolicy, FallbackResponse, ServedBy};
+use hyperware_openai_core::headers::{CustomHeaders, ORGANIZATION_HEADER, PROJECT_HEADER};
+use hyperware_openai_core::metadata::ResponseMetadata;
+use hyperware_openai_core::rate_limit::{RateLimiter, Throttle};
+use hyperware_openai_core::retry::{send_streaming_with_retry, send_with_retry, RetryPolicy};
+use hyperware_openai_core::speech::SpeechError;
+use hyperware_openai_core::sse::SseParser;
+use hyperware_openai_core::transport::{HttpRequest, HttpTransport, HyperwareTransport};
 use hyperware_process_lib::http::client::HttpClientError;
-use http::Method;
+use http::{HeaderMap, Method};
 use std::collections::HashMap;
+use std::sync::Arc;
 
-const MAX_INPUT_LENGTH: usize = 4096;
+/// Input limit of the speech endpoint, in characters (Unicode scalar values).
+pub const MAX_INPUT_LENGTH: usize = 4096;
 const MIN_SPEED: f32 = 0.25;
 const MAX_SPEED: f32 = 4.0;
 
 pub struct SpeechClient {
-    api_key: String,
-    base_url: String,
+    profile: ApiProfile,
     timeout: u64,
+    transport: Arc<dyn HttpTransport>,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    default_headers: CustomHeaders,
+    capabilities: Option<ServerCapabilities>,
 }
 
 impl SpeechClient {
+    /// A client for OpenAI's API.
     pub fn new(api_key: impl Into<String>) -> Self {
+        Self::from_profile(ApiProfile::openai(api_key))
+    }
+
+    /// A client for the endpoint and credentials of `profile`, e.g. an Azure
+    /// OpenAI deployment.
+    pub fn from_profile(profile: ApiProfile) -> Self {
         Self {
-            api_key: api_key.into(),
-            base_url: "https://api.openai.com".to_string(),
+            profile,
             timeout: 60000, // 60 seconds default
+            transport: Arc::new(HyperwareTransport),
+            retry_policy: RetryPolicy::none(),
+            rate_limiter: None,
+            circuit_breaker: None,
+            default_headers: CustomHeaders::new(),
+            capabilities: None,
         }
     }
 
+    /// Replaces the host of the endpoint, e.g. to go through a proxy.
     pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
-        self.base_url = base_url.into();
+        self.profile.endpoint.set_base_url(base_url);
         self
     }
 
@@ -37,33 +71,250 @@ impl SpeechClient {
         self
     }
 
-    pub fn synthesize(&self) -> SpeechRequestBuilder {
+    /// Replaces the Hyperware HTTP client, e.g. with an `InMemoryTransport` in tests.
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Retries rate-limited, overloaded and failed requests. Requests are sent
+    /// once by default.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Delays requests to stay within `rate_limiter`, which may be shared with
+    /// other clients using the same account.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Fails requests at once with `TtsError::CircuitOpen` while
+    /// `circuit_breaker` reports their endpoint as failing. The breaker may be
+    /// shared with other clients.
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// The circuit of the client's endpoint, if a circuit breaker is set.
+    pub fn circuit_status(&self) -> Option<CircuitStatus> {
+        self.circuit_breaker
+            .as_ref()
+            .map(|breaker| breaker.status(&self.profile.endpoint))
+    }
+
+    /// Adds a header to every request. Headers set on a request builder take
+    /// precedence.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    /// Bills requests to this organization (`OpenAI-Organization`).
+    pub fn with_organization(self, organization: impl Into<String>) -> Self {
+        self.with_header(ORGANIZATION_HEADER, organization)
+    }
+
+    /// Bills requests to this project (`OpenAI-Project`).
+    pub fn with_project(self, project: impl Into<String>) -> Self {
+        self.with_header(PROJECT_HEADER, project)
+    }
+
+    /// Rejects requests for models, voices or response formats the server does
+    /// not list, before sending them. See [`probe_capabilities`](Self::probe_capabilities).
+    pub fn with_capabilities(mut self, capabilities: ServerCapabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    pub fn synthesize(&self) -> SpeechRequestBuilder<'_> {
         SpeechRequestBuilder {
             client: self,
             request: SpeechRequest::default(),
+            long: false,
+        }
+    }
+
+    /// Like [`synthesize`](Self::synthesize), but input over the length limit is
+    /// split at paragraph and sentence boundaries, synthesized piece by piece
+    /// and joined into one stream. Supports mp3, wav and pcm output.
+    pub fn synthesize_long(&self) -> SpeechRequestBuilder<'_> {
+        SpeechRequestBuilder {
+            client: self,
+            request: SpeechRequest::default(),
+            long: true,
+        }
+    }
+
+    /// Asks the server which models it serves and, where the server has a
+    /// voice list, which voices, for [`with_capabilities`](Self::with_capabilities).
+    pub async fn probe_capabilities(&self) -> Result<ServerCapabilities, TtsError> {
+        let body = self.get(MODELS_ROUTE).await?;
+        let models = ServerCapabilities::parse_models(&body)
+            .map_err(|e| TtsError::ParseError(e.to_string()))?;
+        // OpenAI has no voice list; a failed lookup only means voices are unchecked
+        let voices = match self.get(VOICES_ROUTE).await {
+            Ok(body) => ServerCapabilities::parse_voices(&body),
+            Err(_) => None,
+        };
+        Ok(ServerCapabilities {
+            models,
+            voices,
+            formats: None,
+        })
+    }
+
+    async fn send_long_speech_request(
+        &self,
+        request: SpeechRequest,
+        on_audio: &mut dyn FnMut(&[u8]),
+    ) -> Result<SpeechResponse, TtsError> {
+        if request.input.chars().count() <= MAX_INPUT_LENGTH {
+            return self.send_speech_request(request, on_audio).await;
+        }
+
+        let format = request.response_format.clone().unwrap_or_default();
+        if !splitting::can_concat(&format) {
+            return Err(TtsError::InvalidAudioFormat(format!(
+                "long-form synthesis supports mp3, wav and pcm, not {}",
+                format.as_str()
+            )));
+        }
+
+        let pieces = splitting::split_text(&request.input, MAX_INPUT_LENGTH);
+        let mut parts = Vec::with_capacity(pieces.len());
+        let mut usage: Option<SpeechUsage> = None;
+        let mut metadata = ResponseMetadata::default();
+        for piece in pieces {
+            let mut piece_request = request.clone();
+            piece_request.input = piece;
+            let response = self.send_speech_request(piece_request, on_audio).await?;
+            parts.push(response.audio_data);
+            if let Some(piece_usage) = response.usage {
+                let total = usage.get_or_insert_with(SpeechUsage::default);
+                total.input_tokens += piece_usage.input_tokens;
+                total.output_tokens += piece_usage.output_tokens;
+                total.total_tokens += piece_usage.total_tokens;
+            }
+            metadata = response.metadata;
         }
+
+        Ok(SpeechResponse {
+            audio_data: splitting::concat_audio(&format, parts)?,
+            format,
+            usage,
+            metadata,
+        })
     }
 
+    /// Sends `request`, then each target of `policy` in turn while the
+    /// failures are ones the policy falls back on.
+    async fn send_fallback_speech_request(
+        &self,
+        request: SpeechRequest,
+        long: bool,
+        policy: &FallbackPolicy<TtsModel>,
+    ) -> Result<FallbackResponse<SpeechResponse>, TtsError> {
+        let mut attempt = request.clone();
+        let mut index = 0;
+        loop {
+            let served_by = ServedBy {
+                index,
+                model: attempt.model.as_str().to_string(),
+                endpoint: attempt.profile.as_ref().unwrap_or(&self.profile).endpoint.clone(),
+            };
+            let result = if long {
+                self.send_long_speech_request(attempt, &mut |_| {}).await
+            } else {
+                self.send_speech_request(attempt, &mut |_| {}).await
+            };
+            let error = match result {
+                Ok(response) => return Ok(FallbackResponse { response, served_by }),
+                Err(error) => error,
+            };
+
+            let Some(target) = policy.targets.get(index) else {
+                return Err(error);
+            };
+            if !policy.falls_back_on(&error.clone().into()) {
+                return Err(error);
+            }
+
+            attempt = request.clone();
+            attempt.model = target.model.clone();
+            attempt.profile = target.profile.clone();
+            if attempt.profile.as_ref().unwrap_or(&self.profile).endpoint.enforces_openai_models() {
+                adapt_to_model(&mut attempt);
+            }
+            index += 1;
+        }
+    }
+
+    /// Sends one speech request, passing audio to `on_audio` as it is decoded.
     async fn send_speech_request(
         &self,
         request: SpeechRequest,
+        on_audio: &mut dyn FnMut(&[u8]),
     ) -> Result<SpeechResponse, TtsError> {
         // Validate input
         if request.input.is_empty() {
             return Err(TtsError::MissingInput);
         }
 
-        if request.input.len() > MAX_INPUT_LENGTH {
-            return Err(TtsError::InputTooLong(request.input.len()));
+        let characters = request.input.chars().count();
+        if characters > MAX_INPUT_LENGTH {
+            return Err(TtsError::InputTooLong {
+                characters,
+                bytes: request.input.len(),
+            });
         }
 
         if let Some(speed) = request.speed {
-            if speed < MIN_SPEED || speed > MAX_SPEED {
+            if !(MIN_SPEED..=MAX_SPEED).contains(&speed) {
                 return Err(TtsError::InvalidSpeed(speed));
             }
         }
 
-        if self.api_key.is_empty() {
+        // Capabilities describe the client's endpoint, not one set on the request
+        let capabilities = self.capabilities.as_ref().filter(|_| request.profile.is_none());
+        if let Some(capabilities) = capabilities {
+            if !capabilities.supports_model(request.model.as_str()) {
+                return Err(TtsError::InvalidModel(format!(
+                    "{} is not served by this server",
+                    request.model.as_str()
+                )));
+            }
+            if !capabilities.supports_voice(request.voice.as_str()) {
+                return Err(TtsError::InvalidVoice(format!(
+                    "{} is not offered by this server",
+                    request.voice.as_str()
+                )));
+            }
+            if let Some(format) = &request.response_format {
+                if !capabilities.supports_format(format.as_str()) {
+                    return Err(TtsError::InvalidAudioFormat(format!(
+                        "{} is not produced by this server",
+                        format.as_str()
+                    )));
+                }
+            }
+        }
+
+        let profile = request.profile.as_ref().unwrap_or(&self.profile);
+        if request.stream_format == Some(StreamFormat::Sse)
+            && profile.endpoint.enforces_openai_models()
+            && matches!(request.model, TtsModel::Tts1 | TtsModel::Tts1Hd)
+        {
+            return Err(TtsError::InvalidModel(format!(
+                "{} does not support stream_format sse",
+                request.model.as_str()
+            )));
+        }
+
+        if profile.auth.is_missing() {
             return Err(TtsError::MissingApiKey);
         }
 
@@ -76,59 +327,247 @@ impl SpeechClient {
 
         // Prepare headers
         let mut headers = HashMap::new();
-        headers.insert("Authorization".to_string(), format!("Bearer {}", self.api_key));
+        profile.auth.apply_to(&mut headers);
         headers.insert("Content-Type".to_string(), "application/json".to_string());
+        self.default_headers.apply_to(&mut headers);
+        request.headers.apply_to(&mut headers);
 
         // Construct URL
-        let url = url::Url::parse(&format!("{}/v1/audio/speech", self.base_url))
-            .map_err(|e| TtsError::HttpClient(HttpClientError::BadUrl {
-                url: e.to_string(),
-            }))?;
+        let url = profile.endpoint.url("audio/speech").map_err(|e| {
+            TtsError::HttpClient(HttpClientError::BadUrl { url: e.to_string() })
+        })?;
 
         // Send request
-        let response = send_request_await_response(
-            Method::POST,
+        let http_request = HttpRequest {
+            method: Method::POST,
             url,
-            Some(headers),
-            self.timeout,
+            headers,
+            timeout: self.timeout,
             body,
+        };
+        self.check_circuit(&profile.endpoint)?;
+        let format = request.response_format.unwrap_or(AudioFormat::Mp3);
+        let result = self.send_speech(http_request, format, on_audio).await;
+        self.record_outcome(&profile.endpoint, &result);
+        result
+    }
+
+    async fn send_speech(
+        &self,
+        http_request: HttpRequest,
+        format: AudioFormat,
+        on_audio: &mut dyn FnMut(&[u8]),
+    ) -> Result<SpeechResponse, TtsError> {
+        let mut stream = AudioStream::new(on_audio);
+        let response = send_streaming_with_retry(
+            self.transport.as_ref(),
+            &self.retry_policy,
+            self.throttle(),
+            http_request,
+            &mut |headers, chunk| stream.feed(headers, chunk),
         )
         .await
         .map_err(TtsError::HttpClient)?;
 
         // Handle response
-        let status = response.status();
-        let body = response.into_body();
+        let (parts, body) = response.into_parts();
+        let (status, headers) = (parts.status, parts.headers);
 
         if status.is_success() {
-            // Success - body contains raw audio data
-            let format = request.response_format.unwrap_or(AudioFormat::Mp3);
+            let (audio_data, usage) = stream.finish()?;
             Ok(SpeechResponse {
-                audio_data: body,
+                audio_data,
                 format,
+                usage,
+                metadata: ResponseMetadata::from_headers(&headers),
             })
         } else {
-            // Try to parse error response
-            if let Ok(error_response) = serde_json::from_slice::<ApiErrorResponse>(&body) {
-                Err(TtsError::ApiError {
-                    status: status.as_u16(),
-                    message: error_response.error.message,
-                })
-            } else {
-                // Fallback to raw body text
-                let message = String::from_utf8_lossy(&body).to_string();
-                Err(TtsError::ApiError {
-                    status: status.as_u16(),
-                    message,
-                })
+            Err(ApiErrorDetails::from_response(status.as_u16(), &headers, &body).into())
+        }
+    }
+
+    /// What each attempt of a request takes from the rate limiter, if any.
+    fn throttle(&self) -> Option<Throttle<'_>> {
+        self.rate_limiter
+            .as_deref()
+            .map(|limiter| Throttle::new(limiter, 0.0))
+    }
+
+    /// Fails fast while the circuit of `endpoint` is open.
+    fn check_circuit(&self, endpoint: &Endpoint) -> Result<(), TtsError> {
+        match &self.circuit_breaker {
+            Some(breaker) => breaker.try_acquire(endpoint).map_err(|retry_after| {
+                TtsError::CircuitOpen {
+                    endpoint: endpoint.to_string(),
+                    retry_after_ms: retry_after.as_millis() as u64,
+                }
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn record_outcome<T>(&self, endpoint: &Endpoint, result: &Result<T, TtsError>) {
+        if let Some(breaker) = &self.circuit_breaker {
+            let error = result.as_ref().err().cloned().map(SpeechError::from);
+            breaker.record(endpoint, error.as_ref());
+        }
+    }
+
+    /// Sends `GET` to `route` and returns the body of a successful response.
+    async fn get(&self, route: &str) -> Result<Vec<u8>, TtsError> {
+        if self.profile.auth.is_missing() {
+            return Err(TtsError::MissingApiKey);
+        }
+
+        let mut headers = HashMap::new();
+        self.profile.auth.apply_to(&mut headers);
+        self.default_headers.apply_to(&mut headers);
+        let url = self.profile.endpoint.url(route).map_err(|e| {
+            TtsError::HttpClient(HttpClientError::BadUrl { url: e.to_string() })
+        })?;
+
+        let http_request = HttpRequest {
+            method: Method::GET,
+            url,
+            headers,
+            timeout: self.timeout,
+            body: Vec::new(),
+        };
+        self.check_circuit(&self.profile.endpoint)?;
+        let result = self.send_get(http_request).await;
+        self.record_outcome(&self.profile.endpoint, &result);
+        result
+    }
+
+    async fn send_get(&self, http_request: HttpRequest) -> Result<Vec<u8>, TtsError> {
+        let throttle = self.throttle();
+        let transport = self.transport.as_ref();
+        let response = send_with_retry(transport, &self.retry_policy, throttle, http_request)
+            .await
+            .map_err(TtsError::HttpClient)?;
+
+        let (parts, body) = response.into_parts();
+        if parts.status.is_success() {
+            Ok(body)
+        } else {
+            let status = parts.status.as_u16();
+            Err(ApiErrorDetails::from_response(status, &parts.headers, &body).into())
+        }
+    }
+}
+
+/// Drops parameters the request's model does not support: tts-1 and tts-1-hd
+/// take no instructions and cannot stream SSE events.
+fn adapt_to_model(request: &mut SpeechRequest) {
+    if matches!(request.model, TtsModel::Tts1 | TtsModel::Tts1Hd) {
+        request.instructions = None;
+        if request.stream_format == Some(StreamFormat::Sse) {
+            request.stream_format = None;
+        }
+    }
+}
+
+/// Decodes the speech response body as the transport delivers it: raw
+/// audio is passed on as is, and SSE events are parsed as they complete.
+struct AudioStream<'a> {
+    on_audio: &'a mut dyn FnMut(&[u8]),
+    parser: SseParser,
+    audio: Vec<u8>,
+    usage: Option<SpeechUsage>,
+    /// The first decoding error; later chunks are ignored.
+    error: Option<TtsError>,
+}
+
+impl<'a> AudioStream<'a> {
+    fn new(on_audio: &'a mut dyn FnMut(&[u8])) -> Self {
+        Self {
+            on_audio,
+            parser: SseParser::new(),
+            audio: Vec::new(),
+            usage: None,
+            error: None,
+        }
+    }
+
+    fn feed(&mut self, headers: &HeaderMap, chunk: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
+        if !is_event_stream(headers) {
+            (self.on_audio)(chunk);
+            self.audio.extend_from_slice(chunk);
+            return;
+        }
+        for sse_event in self.parser.feed(chunk) {
+            if let Err(error) = self.handle_event(&sse_event.data) {
+                self.error = Some(error);
+                return;
             }
         }
     }
+
+    /// Collects the audio of `speech.audio.delta` events and the usage of
+    /// `speech.audio.done`.
+    fn handle_event(&mut self, data: &str) -> Result<(), TtsError> {
+        match decode_stream_event(data)? {
+            Some(SpeechStreamEvent::Delta(delta)) => {
+                let chunk = delta.decode()?;
+                (self.on_audio)(&chunk);
+                self.audio.extend_from_slice(&chunk);
+            }
+            Some(SpeechStreamEvent::Done(done)) => self.usage = done.usage,
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(Vec<u8>, Option<SpeechUsage>), TtsError> {
+        if self.error.is_none() {
+            for sse_event in std::mem::take(&mut self.parser).finish() {
+                self.handle_event(&sse_event.data)?;
+            }
+        }
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok((self.audio, self.usage)),
+        }
+    }
+}
+
+fn is_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_ascii_lowercase().starts_with("text/event-stream"))
+        .unwrap_or(false)
+}
+
+/// Decodes the data of one SSE event, skipping `[DONE]` and unknown event types.
+pub(crate) fn decode_stream_event(data: &str) -> Result<Option<SpeechStreamEvent>, TtsError> {
+    if data.trim() == "[DONE]" {
+        return Ok(None);
+    }
+
+    let value: serde_json::Value =
+        serde_json::from_str(data).map_err(|e| TtsError::ParseError(e.to_string()))?;
+    let known = value
+        .get("type")
+        .and_then(|event_type| event_type.as_str())
+        .map(|event_type| SpeechStreamEvent::KNOWN_TYPES.contains(&event_type))
+        .unwrap_or(false);
+    if !known {
+        return Ok(None);
+    }
+
+    serde_json::from_value(value)
+        .map(Some)
+        .map_err(|e| TtsError::ParseError(e.to_string()))
 }
 
 pub struct SpeechRequestBuilder<'a> {
     client: &'a SpeechClient,
     request: SpeechRequest,
+    long: bool,
 }
 
 impl<'a> SpeechRequestBuilder<'a> {
@@ -162,8 +601,76 @@ impl<'a> SpeechRequestBuilder<'a> {
         self
     }
 
+    /// Selects raw chunked audio or SSE events; see [`StreamFormat`].
+    pub fn stream_format(mut self, stream_format: StreamFormat) -> Self {
+        self.request.stream_format = Some(stream_format);
+        self
+    }
+
+    /// Sets a header on this request, replacing a client default of the same name.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.request.headers.insert(name, value);
+        self
+    }
+
+    /// Bills this request to `organization` instead of the client's.
+    pub fn organization(self, organization: impl Into<String>) -> Self {
+        self.header(ORGANIZATION_HEADER, organization)
+    }
+
+    /// Bills this request to `project` instead of the client's.
+    pub fn project(self, project: impl Into<String>) -> Self {
+        self.header(PROJECT_HEADER, project)
+    }
+
     pub async fn execute(self) -> Result<SpeechResponse, TtsError> {
-        self.client.send_speech_request(self.request).await
+        self.execute_stream(|_| {}).await
+    }
+
+    /// Synthesizes speech, passing audio to `on_audio` chunk by chunk as it
+    /// is received: each `speech.audio.delta` with [`StreamFormat::Sse`], or the
+    /// response body as the transport reads it otherwise. How early audio
+    /// arrives depends on the transport: the default `HyperwareTransport`
+    /// receives the body complete, so it is handed over once downloaded. For
+    /// long-form synthesis the pieces follow one another, each in the
+    /// requested format.
+    ///
+    /// Returns the complete audio, as `execute` does.
+    pub async fn execute_stream<F>(self, mut on_audio: F) -> Result<SpeechResponse, TtsError>
+    where
+        F: FnMut(&[u8]),
+    {
+        if self.long {
+            self.client
+                .send_long_speech_request(self.request, &mut on_audio)
+                .await
+        } else {
+            self.client.send_speech_request(self.request, &mut on_audio).await
+        }
+    }
+
+    /// Like [`execute`](Self::execute), falling back to the targets of `policy`
+    /// when the request fails. Parameters a fallback model does not support
+    /// are dropped, e.g. `instructions` for tts-1.
+    pub async fn execute_with_fallback(
+        self,
+        policy: &FallbackPolicy<TtsModel>,
+    ) -> Result<FallbackResponse<SpeechResponse>, TtsError> {
+        self.client
+            .send_fallback_speech_request(self.request, self.long, policy)
+            .await
+    }
+
+    /// Synthesizes speech for a phone call: requests `pcm`, downsamples it to
+    /// 8 kHz and encodes it as G.711 frames. Any `response_format` is replaced.
+    pub async fn execute_telephony(
+        mut self,
+        options: TelephonyOptions,
+    ) -> Result<TelephonyAudio, TtsError> {
+        self.request.response_format = Some(AudioFormat::Pcm);
+        let response = self.execute().await?;
+        let pcm = PcmBuffer::from_speech(&response.audio_data)?;
+        Ok(TelephonyAudio::encode(&options, &pcm, response))
     }
 }
 
@@ -1,3 +1,6 @@
+use hyperware_openai_core::api_error::{ApiErrorDetails, ApiErrorKind};
+use hyperware_openai_core::retry::is_transient_client_error;
+use hyperware_openai_core::speech::{SpeechError, SpeechErrorKind};
 use hyperware_process_lib::http::client::HttpClientError;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -10,16 +13,118 @@ pub enum TtsError {
     InvalidModel(String),
     #[error("Invalid voice: {0}")]
     InvalidVoice(String),
-    #[error("Input text too long (max 4096 characters), got {0} characters")]
-    InputTooLong(usize),
+    #[error("Invalid audio format: {0}")]
+    InvalidAudioFormat(String),
+    #[error("Input text too long (max 4096 characters), got {characters} characters ({bytes} bytes)")]
+    InputTooLong { characters: usize, bytes: usize },
     #[error("Invalid speed: {0} (must be between 0.25 and 4.0)")]
     InvalidSpeed(f32),
     #[error("API error: {status} - {message}")]
     ApiError { status: u16, message: String },
+    #[error("Authentication failed: {0}")]
+    Authentication(Box<ApiErrorDetails>),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(Box<ApiErrorDetails>),
+    #[error("Rate limited: {0}")]
+    RateLimited(Box<ApiErrorDetails>),
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(Box<ApiErrorDetails>),
+    #[error("Invalid request: {0}")]
+    InvalidRequest(Box<ApiErrorDetails>),
+    #[error("Server error: {0}")]
+    ServerError(Box<ApiErrorDetails>),
+    #[error("Request timed out: {0}")]
+    Timeout(Box<ApiErrorDetails>),
     #[error("Missing API key")]
     MissingApiKey,
+    #[error("Circuit open for {endpoint}, retry in {retry_after_ms} ms")]
+    CircuitOpen { endpoint: String, retry_after_ms: u64 },
     #[error("Input text is required")]
     MissingInput,
     #[error("Failed to serialize request: {0}")]
     SerializationError(String),
-}
\ No newline at end of file
+    #[error("Failed to parse response: {0}")]
+    ParseError(String),
+}
+
+impl TtsError {
+    /// Details of a classified API error response.
+    pub fn api_details(&self) -> Option<&ApiErrorDetails> {
+        match self {
+            TtsError::Authentication(details)
+            | TtsError::PermissionDenied(details)
+            | TtsError::RateLimited(details)
+            | TtsError::QuotaExceeded(details)
+            | TtsError::InvalidRequest(details)
+            | TtsError::ServerError(details)
+            | TtsError::Timeout(details) => Some(details.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Whether sending the same request again later may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            TtsError::HttpClient(error) => is_transient_client_error(error),
+            TtsError::RateLimited(_)
+            | TtsError::ServerError(_)
+            | TtsError::Timeout(_)
+            | TtsError::CircuitOpen { .. } => true,
+            TtsError::ApiError { status, .. } => *status == 409 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Whether the request, credentials or account must change before it can
+    /// succeed. Rate limiting is not counted, as it clears by itself.
+    pub fn is_client_error(&self) -> bool {
+        match self {
+            TtsError::Authentication(_)
+            | TtsError::PermissionDenied(_)
+            | TtsError::QuotaExceeded(_)
+            | TtsError::InvalidRequest(_)
+            | TtsError::InvalidModel(_)
+            | TtsError::InvalidVoice(_)
+            | TtsError::InvalidAudioFormat(_)
+            | TtsError::InputTooLong { .. }
+            | TtsError::InvalidSpeed(_)
+            | TtsError::MissingApiKey
+            | TtsError::MissingInput => true,
+            TtsError::ApiError { status, .. } => (400..500).contains(status) && *status != 409,
+            _ => false,
+        }
+    }
+}
+
+impl From<ApiErrorDetails> for TtsError {
+    fn from(details: ApiErrorDetails) -> Self {
+        match details.kind() {
+            ApiErrorKind::Authentication => TtsError::Authentication(Box::new(details)),
+            ApiErrorKind::PermissionDenied => TtsError::PermissionDenied(Box::new(details)),
+            ApiErrorKind::RateLimited => TtsError::RateLimited(Box::new(details)),
+            ApiErrorKind::QuotaExceeded => TtsError::QuotaExceeded(Box::new(details)),
+            ApiErrorKind::InvalidRequest => TtsError::InvalidRequest(Box::new(details)),
+            ApiErrorKind::ServerError => TtsError::ServerError(Box::new(details)),
+            ApiErrorKind::Timeout => TtsError::Timeout(Box::new(details)),
+            ApiErrorKind::Other => TtsError::ApiError {
+                status: details.status,
+                message: details.message,
+            },
+        }
+    }
+}
+
+impl From<TtsError> for SpeechError {
+    fn from(error: TtsError) -> Self {
+        let kind = match &error {
+            TtsError::Authentication(_) | TtsError::PermissionDenied(_) | TtsError::MissingApiKey => {
+                SpeechErrorKind::Authentication
+            }
+            TtsError::RateLimited(_) => SpeechErrorKind::RateLimited,
+            _ if error.is_retryable() => SpeechErrorKind::Unavailable,
+            _ if error.is_client_error() => SpeechErrorKind::InvalidRequest,
+            _ => SpeechErrorKind::Other,
+        };
+        SpeechError::new(kind, error.to_string())
+    }
+}
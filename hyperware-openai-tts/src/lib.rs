@@ -1,12 +1,40 @@
 pub mod client;
 pub mod error;
+pub mod pcm;
+mod provider;
+pub mod splitting;
+pub mod telephony;
 pub mod types;
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests;
 
-pub use client::{SpeechClient, SpeechRequestBuilder};
+pub use client::{SpeechClient, SpeechRequestBuilder, MAX_INPUT_LENGTH};
 pub use error::TtsError;
+pub use pcm::{PcmBuffer, SPEECH_SAMPLE_RATE};
+pub use splitting::{concat_audio, split_text};
+pub use telephony::{G711Codec, TelephonyAudio, TelephonyOptions, TELEPHONY_SAMPLE_RATE};
+pub use hyperware_openai_core::api_error::{ApiErrorDetails, ApiErrorKind};
+pub use hyperware_openai_core::capabilities::ServerCapabilities;
+pub use hyperware_openai_core::circuit_breaker::{
+    CircuitBreaker, CircuitBreakerPolicy, CircuitState, CircuitStatus,
+};
+pub use hyperware_openai_core::endpoint::{ApiProfile, Auth, Endpoint};
+pub use hyperware_openai_core::fallback::{
+    FallbackPolicy, FallbackResponse, FallbackTarget, ServedBy,
+};
+pub use hyperware_openai_core::headers::CustomHeaders;
+pub use hyperware_openai_core::metadata::{RateLimitInfo, ResponseMetadata};
+pub use hyperware_openai_core::rate_limit::RateLimiter;
+pub use hyperware_openai_core::retry::RetryPolicy;
+pub use hyperware_openai_core::speech::{
+    SpeechError, SpeechErrorKind, SynthesizedSpeech, TextToSpeech, TextToSpeechRequest,
+};
+pub use hyperware_openai_core::transport::{
+    HttpRequest, HttpTransport, HyperwareTransport, InMemoryTransport,
+};
 pub use types::{
-    AudioFormat, SpeechRequest, SpeechResponse, TtsModel, Voice,
+    AudioFormat, SpeechAudioDelta, SpeechAudioDone, SpeechRequest, SpeechResponse,
+    SpeechStreamEvent, SpeechUsage, StreamFormat, TtsModel, Voice,
 };
\ No newline at end of file
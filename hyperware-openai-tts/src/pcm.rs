@@ -0,0 +1,129 @@
+use crate::error::TtsError;
+use crate::types::{AudioFormat, SpeechResponse};
+use hyperware_openai_core::audio::write_wav;
+use std::f64::consts::PI;
+use std::time::Duration;
+
+pub use hyperware_openai_core::audio::fmt_chunk;
+
+/// Sample rate of `AudioFormat::Pcm` speech: 24 kHz, 16-bit little-endian, mono.
+pub const SPEECH_SAMPLE_RATE: u32 = 24_000;
+
+/// Mono 16-bit PCM audio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PcmBuffer {
+    pub samples: Vec<i16>,
+    pub sample_rate: u32,
+}
+
+impl PcmBuffer {
+    pub fn new(samples: Vec<i16>, sample_rate: u32) -> Self {
+        Self {
+            samples,
+            sample_rate,
+        }
+    }
+
+    /// Reads 16-bit little-endian samples. A trailing odd byte is an error.
+    pub fn from_le_bytes(bytes: &[u8], sample_rate: u32) -> Result<Self, TtsError> {
+        if !bytes.len().is_multiple_of(2) {
+            return Err(TtsError::InvalidAudioFormat(format!(
+                "16-bit PCM needs an even number of bytes, got {}",
+                bytes.len()
+            )));
+        }
+        let samples = bytes
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        Ok(Self::new(samples, sample_rate))
+    }
+
+    /// Interprets the output of a `pcm` speech request.
+    pub fn from_speech(bytes: &[u8]) -> Result<Self, TtsError> {
+        Self::from_le_bytes(bytes, SPEECH_SAMPLE_RATE)
+    }
+
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.samples
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect()
+    }
+
+    pub fn duration(&self) -> Duration {
+        if self.sample_rate == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(self.samples.len() as f64 / self.sample_rate as f64)
+    }
+
+    /// Wraps the samples in a WAV file.
+    pub fn to_wav(&self) -> Vec<u8> {
+        write_wav(&fmt_chunk(self.sample_rate, 1, 16), &self.to_le_bytes())
+    }
+
+    /// Converts to another sample rate, e.g. 8000 for telephony or 48000 for
+    /// WebRTC, by windowed-sinc interpolation. The filter cuts off just below
+    /// the lower of the two Nyquist limits, so downsampling does not alias.
+    pub fn resample(&self, sample_rate: u32) -> Self {
+        if sample_rate == self.sample_rate {
+            return self.clone();
+        }
+        if self.samples.is_empty() || self.sample_rate == 0 || sample_rate == 0 {
+            return Self::new(Vec::new(), sample_rate);
+        }
+
+        let ratio = self.sample_rate as f64 / sample_rate as f64;
+        // In cycles per source sample; downsampling narrows the filter
+        let cutoff = SINC_CUTOFF / ratio.max(1.0);
+        let half_width = SINC_HALF_TAPS * ratio.max(1.0);
+
+        let len = ((self.samples.len() as f64) / ratio).round() as usize;
+        let last = self.samples.len() - 1;
+        let samples = (0..len)
+            .map(|index| {
+                let position = index as f64 * ratio;
+                let first = (position - half_width).ceil().max(0.0) as usize;
+                let end = ((position + half_width).floor() as usize).min(last);
+                let (mut sum, mut weight) = (0.0, 0.0);
+                for (source, &sample) in self.samples.iter().enumerate().take(end + 1).skip(first) {
+                    let tap = windowed_sinc(source as f64 - position, cutoff, half_width);
+                    sum += tap * sample as f64;
+                    weight += tap;
+                }
+                // Normalizing keeps the gain at 1 where the edges cut the filter short
+                let value = if weight > 0.0 { sum / weight } else { 0.0 };
+                value.round().clamp(i16::MIN as f64, i16::MAX as f64) as i16
+            })
+            .collect();
+        Self::new(samples, sample_rate)
+    }
+}
+
+impl SpeechResponse {
+    /// The audio as samples, when it was requested as `pcm`.
+    pub fn pcm(&self) -> Option<Result<PcmBuffer, TtsError>> {
+        matches!(self.format, AudioFormat::Pcm).then(|| PcmBuffer::from_speech(&self.audio_data))
+    }
+}
+
+/// Resampling filter cutoff as a fraction of the lower sample rate, leaving
+/// room for the transition band below its Nyquist limit of 0.5.
+const SINC_CUTOFF: f64 = 0.45;
+
+/// Filter taps on each side of an output sample, counted at the lower rate.
+const SINC_HALF_TAPS: f64 = 16.0;
+
+/// Blackman-windowed sinc low-pass with `cutoff` in cycles per sample,
+/// evaluated `t` samples from its center.
+fn windowed_sinc(t: f64, cutoff: f64, half_width: f64) -> f64 {
+    if t.abs() >= half_width {
+        return 0.0;
+    }
+    let x = 2.0 * cutoff * t;
+    let sinc = if x == 0.0 { 1.0 } else { (PI * x).sin() / (PI * x) };
+    let phase = PI * t / half_width;
+    let window = 0.42 + 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+    2.0 * cutoff * sinc * window
+}
@@ -0,0 +1,35 @@
+use crate::client::SpeechClient;
+use hyperware_openai_core::speech::{
+    SpeechFuture, SynthesizedSpeech, TextToSpeech, TextToSpeechRequest,
+};
+
+impl TextToSpeech for SpeechClient {
+    /// Synthesizes with the [`synthesize_long`](SpeechClient::synthesize_long)
+    /// builder, so text over the input limit is split and joined.
+    fn text_to_speech(&self, request: TextToSpeechRequest) -> SpeechFuture<'_, SynthesizedSpeech> {
+        Box::pin(async move {
+            let mut builder = self.synthesize_long().input(request.text);
+            if let Some(model) = &request.model {
+                builder = builder.model(model.parse()?);
+            }
+            if let Some(voice) = &request.voice {
+                builder = builder.voice(voice.parse()?);
+            }
+            if let Some(format) = &request.format {
+                builder = builder.response_format(format.parse()?);
+            }
+            if let Some(speed) = request.speed {
+                builder = builder.speed(speed);
+            }
+            if let Some(instructions) = request.instructions {
+                builder = builder.instructions(instructions);
+            }
+
+            let response = builder.execute().await?;
+            Ok(SynthesizedSpeech {
+                format: response.format.as_str().to_string(),
+                audio: response.audio_data,
+            })
+        })
+    }
+}
@@ -0,0 +1,161 @@
+use crate::error::TtsError;
+use crate::types::AudioFormat;
+use hyperware_openai_core::audio::{id3v2_len, parse_wav, write_wav};
+
+/// Splits `text` into pieces of at most `max_len` characters for long-form synthesis.
+///
+/// Pieces break at paragraph boundaries where possible, then at sentence ends,
+/// then between words; only a single word longer than `max_len` is cut
+/// mid-word. Whitespace at the edges of each piece is trimmed.
+pub fn split_text(text: &str, max_len: usize) -> Vec<String> {
+    let max_len = max_len.max(1);
+    let mut pieces = Vec::new();
+    collect_pieces(text, max_len, Level::Paragraph, &mut pieces);
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    for piece in pieces {
+        let piece_len = piece.chars().count();
+        if current_len + piece_len > max_len {
+            push_trimmed(&mut chunks, &mut current);
+            current_len = 0;
+        }
+        current.push_str(piece);
+        current_len += piece_len;
+    }
+    push_trimmed(&mut chunks, &mut current);
+    chunks
+}
+
+#[derive(Clone, Copy)]
+enum Level {
+    Paragraph,
+    Sentence,
+    Word,
+    Char,
+}
+
+/// Breaks `text` into pieces no longer than `max_len`, using the coarsest
+/// boundary that makes each piece fit. Concatenating the pieces gives `text`.
+fn collect_pieces<'a>(text: &'a str, max_len: usize, level: Level, pieces: &mut Vec<&'a str>) {
+    if text.chars().count() <= max_len {
+        pieces.push(text);
+        return;
+    }
+
+    let (parts, next): (Vec<&str>, Level) = match level {
+        Level::Paragraph => (text.split_inclusive("\n\n").collect(), Level::Sentence),
+        Level::Sentence => (split_sentences(text), Level::Word),
+        Level::Word => (text.split_inclusive(char::is_whitespace).collect(), Level::Char),
+        Level::Char => {
+            let mut rest = text;
+            while let Some((cut, _)) = rest.char_indices().nth(max_len) {
+                pieces.push(&rest[..cut]);
+                rest = &rest[cut..];
+            }
+            pieces.push(rest);
+            return;
+        }
+    };
+
+    for part in parts {
+        collect_pieces(part, max_len, next, pieces);
+    }
+}
+
+/// Splits after sentence-ending punctuation and the whitespace that follows it.
+/// Full-width CJK terminators end a sentence even without whitespace.
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        let ends_sentence = match c {
+            '。' | '！' | '？' => true,
+            '.' | '!' | '?' | '…' => chars
+                .peek()
+                .map(|(_, next)| next.is_whitespace())
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !ends_sentence {
+            continue;
+        }
+
+        let mut end = index + c.len_utf8();
+        while let Some((next_index, next)) = chars.peek().copied() {
+            if !next.is_whitespace() {
+                break;
+            }
+            end = next_index + next.len_utf8();
+            chars.next();
+        }
+        sentences.push(&text[start..end]);
+        start = end;
+    }
+
+    if start < text.len() {
+        sentences.push(&text[start..]);
+    }
+    sentences
+}
+
+fn push_trimmed(chunks: &mut Vec<String>, current: &mut String) {
+    let trimmed = current.trim();
+    if !trimmed.is_empty() {
+        chunks.push(trimmed.to_string());
+    }
+    current.clear();
+}
+
+/// Whether [`concat_audio`] can join audio of this format.
+pub fn can_concat(format: &AudioFormat) -> bool {
+    matches!(format, AudioFormat::Mp3 | AudioFormat::Wav | AudioFormat::Pcm)
+}
+
+/// Joins separately synthesized pieces into one stream.
+///
+/// WAV pieces are merged under a single header, PCM is appended as-is and MP3
+/// frames are appended after stripping the ID3 tag of every piece but the first.
+pub fn concat_audio(format: &AudioFormat, parts: Vec<Vec<u8>>) -> Result<Vec<u8>, TtsError> {
+    match format {
+        AudioFormat::Pcm => Ok(parts.concat()),
+        AudioFormat::Mp3 => {
+            let mut joined = Vec::with_capacity(parts.iter().map(Vec::len).sum());
+            for (index, part) in parts.iter().enumerate() {
+                let skip = if index == 0 { 0 } else { id3v2_len(part) };
+                joined.extend_from_slice(&part[skip..]);
+            }
+            Ok(joined)
+        }
+        AudioFormat::Wav => {
+            let mut fmt: Option<&[u8]> = None;
+            let mut samples = Vec::new();
+            for part in &parts {
+                let wav = parse_wav(part)
+                    .map_err(|error| TtsError::InvalidAudioFormat(error.to_string()))?;
+                let (part_fmt, part_samples) = (wav.fmt, wav.samples);
+                match fmt {
+                    None => fmt = Some(part_fmt),
+                    Some(fmt) if fmt != part_fmt => {
+                        return Err(TtsError::InvalidAudioFormat(
+                            "WAV pieces have different sample formats".to_string(),
+                        ));
+                    }
+                    Some(_) => {}
+                }
+                samples.extend_from_slice(part_samples);
+            }
+            let fmt = fmt.ok_or_else(|| {
+                TtsError::InvalidAudioFormat("no WAV audio to concatenate".to_string())
+            })?;
+            Ok(write_wav(fmt, &samples))
+        }
+        other => Err(TtsError::InvalidAudioFormat(format!(
+            "{} audio cannot be concatenated; use mp3, wav or pcm",
+            other.as_str()
+        ))),
+    }
+}
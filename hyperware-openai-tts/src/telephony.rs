@@ -0,0 +1,206 @@
+use crate::pcm::PcmBuffer;
+use crate::types::{SpeechResponse, SpeechUsage};
+use hyperware_openai_core::metadata::ResponseMetadata;
+use std::time::Duration;
+
+/// Sample rate of G.711 telephony audio.
+pub const TELEPHONY_SAMPLE_RATE: u32 = 8_000;
+
+/// G.711 companding law: mu-law in North America and Japan, A-law elsewhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum G711Codec {
+    MuLaw,
+    ALaw,
+}
+
+impl G711Codec {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            G711Codec::MuLaw => "mulaw",
+            G711Codec::ALaw => "alaw",
+        }
+    }
+
+    /// The byte that decodes to silence.
+    pub fn silence(&self) -> u8 {
+        match self {
+            G711Codec::MuLaw => 0xFF,
+            G711Codec::ALaw => 0xD5,
+        }
+    }
+
+    /// Encodes samples one byte each. Resample to 8 kHz first.
+    pub fn encode(&self, samples: &[i16]) -> Vec<u8> {
+        let encode = match self {
+            G711Codec::MuLaw => linear_to_mulaw,
+            G711Codec::ALaw => linear_to_alaw,
+        };
+        samples.iter().map(|&sample| encode(sample)).collect()
+    }
+
+    /// Decodes G.711 bytes, e.g. inbound call audio, into 8 kHz samples.
+    pub fn decode(&self, bytes: &[u8]) -> PcmBuffer {
+        let decode = match self {
+            G711Codec::MuLaw => mulaw_to_linear,
+            G711Codec::ALaw => alaw_to_linear,
+        };
+        PcmBuffer::new(
+            bytes.iter().map(|&byte| decode(byte)).collect(),
+            TELEPHONY_SAMPLE_RATE,
+        )
+    }
+
+    /// Decodes G.711 bytes into a 16-bit WAV file that `TranscriptionClient` accepts.
+    pub fn decode_to_wav(&self, bytes: &[u8]) -> Vec<u8> {
+        self.decode(bytes).to_wav()
+    }
+}
+
+/// How [`execute_telephony`](crate::SpeechRequestBuilder::execute_telephony)
+/// encodes speech.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TelephonyOptions {
+    pub codec: G711Codec,
+    /// Audio per frame; 20 ms (160 bytes) by default, as most RTP stacks expect.
+    pub frame_duration: Duration,
+}
+
+impl TelephonyOptions {
+    pub fn new(codec: G711Codec) -> Self {
+        Self {
+            codec,
+            frame_duration: Duration::from_millis(20),
+        }
+    }
+
+    pub fn mulaw() -> Self {
+        Self::new(G711Codec::MuLaw)
+    }
+
+    pub fn alaw() -> Self {
+        Self::new(G711Codec::ALaw)
+    }
+
+    pub fn with_frame_duration(mut self, frame_duration: Duration) -> Self {
+        self.frame_duration = frame_duration;
+        self
+    }
+
+    /// Samples, and so bytes, per frame; at least one.
+    pub fn frame_len(&self) -> usize {
+        let samples = self.frame_duration.as_secs_f64() * TELEPHONY_SAMPLE_RATE as f64;
+        (samples.round() as usize).max(1)
+    }
+
+    /// Downsamples `pcm` to 8 kHz and encodes it in frames of
+    /// [`frame_len`](Self::frame_len) bytes. The last frame is padded with silence.
+    pub fn encode_frames(&self, pcm: &PcmBuffer) -> Vec<Vec<u8>> {
+        let encoded = self.codec.encode(&pcm.resample(TELEPHONY_SAMPLE_RATE).samples);
+        let frame_len = self.frame_len();
+        encoded
+            .chunks(frame_len)
+            .map(|chunk| {
+                let mut frame = chunk.to_vec();
+                frame.resize(frame_len, self.codec.silence());
+                frame
+            })
+            .collect()
+    }
+}
+
+/// Speech encoded for a phone call.
+#[derive(Debug, Clone)]
+pub struct TelephonyAudio {
+    pub codec: G711Codec,
+    /// Equally sized G.711 frames at 8 kHz.
+    pub frames: Vec<Vec<u8>>,
+    pub usage: Option<SpeechUsage>,
+    pub metadata: ResponseMetadata,
+}
+
+impl TelephonyAudio {
+    pub(crate) fn encode(
+        options: &TelephonyOptions,
+        pcm: &PcmBuffer,
+        response: SpeechResponse,
+    ) -> Self {
+        Self {
+            codec: options.codec,
+            frames: options.encode_frames(pcm),
+            usage: response.usage,
+            metadata: response.metadata,
+        }
+    }
+
+    /// All frames as one G.711 stream.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.frames.concat()
+    }
+
+    pub fn duration(&self) -> Duration {
+        let samples: usize = self.frames.iter().map(Vec::len).sum();
+        Duration::from_micros(samples as u64 * 1_000_000 / TELEPHONY_SAMPLE_RATE as u64)
+    }
+}
+
+const MULAW_BIAS: i32 = 0x84;
+const MULAW_CLIP: i32 = 32635;
+
+fn linear_to_mulaw(sample: i16) -> u8 {
+    let sample = sample as i32;
+    let sign = if sample < 0 { 0x80 } else { 0x00 };
+    let magnitude = sample.abs().min(MULAW_CLIP) + MULAW_BIAS;
+    // Segment of the highest set bit above bit 7
+    let exponent = (31 - magnitude.leading_zeros() as i32 - 7).clamp(0, 7);
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0F;
+    !(sign | (exponent << 4) as u8 | mantissa as u8)
+}
+
+fn mulaw_to_linear(byte: u8) -> i16 {
+    let byte = !byte;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = (byte & 0x0F) as i32;
+    let magnitude = (((mantissa << 3) + MULAW_BIAS) << exponent) - MULAW_BIAS;
+    if byte & 0x80 != 0 {
+        -magnitude as i16
+    } else {
+        magnitude as i16
+    }
+}
+
+/// Upper bounds of the 13-bit A-law segments.
+const ALAW_SEGMENT_END: [i32; 8] = [0x1F, 0x3F, 0x7F, 0xFF, 0x1FF, 0x3FF, 0x7FF, 0xFFF];
+
+fn linear_to_alaw(sample: i16) -> u8 {
+    let sample = sample as i32 >> 3;
+    let (mask, magnitude) = if sample >= 0 {
+        (0xD5, sample)
+    } else {
+        (0x55, -sample - 1)
+    };
+    let segment = match ALAW_SEGMENT_END.iter().position(|&end| magnitude <= end) {
+        Some(segment) => segment as i32,
+        None => return 0x7F ^ mask,
+    };
+    let shift = if segment < 2 { 1 } else { segment };
+    let value = (segment << 4) | ((magnitude >> shift) & 0x0F);
+    value as u8 ^ mask
+}
+
+fn alaw_to_linear(byte: u8) -> i16 {
+    let byte = byte ^ 0x55;
+    let segment = (byte >> 4) & 0x07;
+    let mut magnitude = ((byte & 0x0F) as i32) << 4;
+    magnitude += match segment {
+        0 => 8,
+        _ => 0x108,
+    };
+    if segment > 1 {
+        magnitude <<= segment - 1;
+    }
+    if byte & 0x80 != 0 {
+        magnitude as i16
+    } else {
+        -magnitude as i16
+    }
+}
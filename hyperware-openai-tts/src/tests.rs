@@ -1,6 +1,26 @@
 #[cfg(test)]
 mod tests {
-    use crate::types::{AudioFormat, SpeechRequestJson, TtsModel, Voice, SpeechRequest};
+    use crate::client::SpeechClient;
+    use crate::client::decode_stream_event;
+    use crate::error::TtsError;
+    use crate::pcm::{fmt_chunk, PcmBuffer, SPEECH_SAMPLE_RATE};
+    use crate::splitting::{concat_audio, split_text};
+    use crate::telephony::{G711Codec, TelephonyOptions, TELEPHONY_SAMPLE_RATE};
+    use hyperware_openai_core::audio::write_wav;
+    use hyperware_openai_core::capabilities::ServerCapabilities;
+    use hyperware_openai_core::circuit_breaker::{CircuitBreaker, CircuitBreakerPolicy, CircuitState};
+    use hyperware_openai_core::endpoint::ApiProfile;
+    use hyperware_openai_core::fallback::FallbackPolicy;
+    use hyperware_openai_core::headers::CustomHeaders;
+    use hyperware_openai_core::rate_limit::RateLimiter;
+    use hyperware_openai_core::speech::{SpeechErrorKind, TextToSpeech, TextToSpeechRequest};
+    use hyperware_openai_core::retry::RetryPolicy;
+    use hyperware_openai_core::transport::InMemoryTransport;
+    use std::sync::Arc;
+    use crate::types::{
+        AudioFormat, SpeechRequest, SpeechRequestJson, SpeechStreamEvent, SpeechUsage, StreamFormat,
+        TtsModel, Voice,
+    };
 
     #[test]
     fn test_model_serialization() {
@@ -43,6 +63,9 @@ mod tests {
             instructions: Some("Speak with enthusiasm".to_string()),
             response_format: Some(AudioFormat::Mp3),
             speed: Some(1.5),
+            stream_format: None,
+            headers: CustomHeaders::new(),
+            profile: None,
         };
 
         let json_request = SpeechRequestJson::from(request);
@@ -65,6 +88,9 @@ mod tests {
             instructions: None,
             response_format: None,
             speed: None,
+            stream_format: None,
+            headers: CustomHeaders::new(),
+            profile: None,
         };
 
         let json_request = SpeechRequestJson::from(request);
@@ -84,4 +110,893 @@ mod tests {
         let default_format = AudioFormat::default();
         assert!(matches!(default_format, AudioFormat::Mp3));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_wire_names_round_trip() {
+        for model in TtsModel::KNOWN {
+            assert_eq!(&model.as_str().parse::<TtsModel>().unwrap(), model);
+            assert_eq!(model.to_string(), model.as_str());
+        }
+        for voice in Voice::KNOWN {
+            assert_eq!(&voice.as_str().parse::<Voice>().unwrap(), voice);
+        }
+        for format in AudioFormat::KNOWN {
+            assert_eq!(&format.as_str().parse::<AudioFormat>().unwrap(), format);
+        }
+
+        assert_eq!(" Alloy ".parse::<Voice>().unwrap(), Voice::Alloy);
+        assert_eq!("TTS-1-HD".parse::<TtsModel>().unwrap(), TtsModel::Tts1Hd);
+    }
+
+    #[test]
+    fn test_custom_names() {
+        let voice: Voice = "marin".parse().unwrap();
+        assert_eq!(voice, Voice::Custom("marin".to_string()));
+        assert!(!voice.is_known());
+        assert_eq!(voice.as_str(), "marin");
+
+        let model: TtsModel = "gpt-5-tts".parse().unwrap();
+        assert_eq!(model.to_string(), "gpt-5-tts");
+
+        assert!(matches!("".parse::<Voice>(), Err(TtsError::InvalidVoice(_))));
+        assert!(matches!("  ".parse::<TtsModel>(), Err(TtsError::InvalidModel(_))));
+        assert!(matches!("".parse::<AudioFormat>(), Err(TtsError::InvalidAudioFormat(_))));
+    }
+
+    #[test]
+    fn test_enum_serde_uses_wire_names() {
+        assert_eq!(serde_json::to_string(&TtsModel::Gpt4oMiniTts).unwrap(), "\"gpt-4o-mini-tts\"");
+        assert_eq!(serde_json::to_string(&Voice::Custom("cedar".to_string())).unwrap(), "\"cedar\"");
+
+        let format: AudioFormat = serde_json::from_str("\"opus\"").unwrap();
+        assert_eq!(format, AudioFormat::Opus);
+        let voice: Voice = serde_json::from_str("\"cedar\"").unwrap();
+        assert_eq!(voice, Voice::Custom("cedar".to_string()));
+        assert!(serde_json::from_str::<Voice>("\"\"").is_err());
+    }
+
+    fn mock_client() -> (SpeechClient, Arc<InMemoryTransport>) {
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = SpeechClient::new("test-key").with_transport(transport.clone());
+        (client, transport)
+    }
+
+    #[tokio::test]
+    async fn test_speech_request_round_trip() {
+        let (client, transport) = mock_client();
+        transport.push_response(
+            200,
+            &[
+                ("content-type", "audio/wav"),
+                ("x-request-id", "req_tts"),
+                ("openai-processing-ms", "310"),
+                ("x-ratelimit-remaining-requests", "42"),
+            ],
+            b"RIFFfakeaudio".to_vec(),
+        );
+
+        let response = client
+            .synthesize()
+            .input("Hello")
+            .voice(Voice::Coral)
+            .response_format(AudioFormat::Wav)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(response.audio_data, b"RIFFfakeaudio");
+        assert_eq!(response.format, AudioFormat::Wav);
+        assert_eq!(response.metadata.request_id.as_deref(), Some("req_tts"));
+        assert_eq!(response.metadata.processing_ms, Some(310));
+        assert_eq!(response.metadata.rate_limit.remaining_requests, Some(42));
+
+        let request = transport.last_request().unwrap();
+        assert_eq!(request.url.as_str(), "https://api.openai.com/v1/audio/speech");
+        assert_eq!(request.headers["Authorization"], "Bearer test-key");
+        let json: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(json["voice"], "coral");
+        assert_eq!(json["response_format"], "wav");
+    }
+
+    #[tokio::test]
+    async fn test_speech_api_error_mapping() {
+        let (client, transport) = mock_client();
+        transport.push_response(
+            429,
+            &[("content-type", "application/json")],
+            r#"{"error": {"message": "Rate limit reached", "type": "requests", "code": "rate_limit_exceeded"}}"#,
+        );
+
+        let result = client.synthesize().input("Hello").execute().await;
+        match result {
+            Err(TtsError::RateLimited(details)) => {
+                assert_eq!(details.status, 429);
+                assert_eq!(details.message, "Rate limit reached");
+                assert_eq!(details.error_type.as_deref(), Some("requests"));
+                assert_eq!(details.code.as_deref(), Some("rate_limit_exceeded"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_speech_retries_rate_limited_request() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = SpeechClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_retry_policy(RetryPolicy::default());
+        transport.push_response(
+            429,
+            &[("retry-after-ms", "150")],
+            r#"{"error": {"message": "Rate limit reached", "type": "requests", "code": "rate_limit_exceeded"}}"#,
+        );
+        transport.push_response(200, &[("content-type", "audio/mpeg")], b"ID3audio".to_vec());
+
+        let response = client.synthesize().input("Hello").execute().await.unwrap();
+        assert_eq!(response.audio_data, b"ID3audio");
+        assert_eq!(transport.requests().len(), 2);
+        assert_eq!(transport.sleeps(), vec![std::time::Duration::from_millis(150)]);
+    }
+
+    #[tokio::test]
+    async fn test_speech_error_classification() {
+        let (client, transport) = mock_client();
+        transport.push_response(
+            400,
+            &[("x-request-id", "req_123")],
+            r#"{"error": {"message": "Invalid value for 'voice'.", "type": "invalid_request_error", "code": "invalid_value", "param": "voice"}}"#,
+        );
+        transport.push_response(403, &[], r#"{"error": {"message": "Project does not have access", "type": "invalid_request_error", "code": null}}"#);
+        transport.push_response(504, &[], "Gateway Timeout");
+        transport.push_response(418, &[], "I'm a teapot");
+
+        let invalid = client.synthesize().input("Hello").execute().await.unwrap_err();
+        let details = invalid.api_details().unwrap();
+        assert!(matches!(invalid, TtsError::InvalidRequest(_)));
+        assert_eq!(details.param.as_deref(), Some("voice"));
+        assert_eq!(details.request_id.as_deref(), Some("req_123"));
+        assert!(invalid.is_client_error());
+        assert!(invalid.to_string().contains("param: voice"));
+
+        let forbidden = client.synthesize().input("Hello").execute().await.unwrap_err();
+        assert!(matches!(forbidden, TtsError::PermissionDenied(_)));
+
+        let timeout = client.synthesize().input("Hello").execute().await.unwrap_err();
+        assert!(matches!(timeout, TtsError::Timeout(_)));
+        assert!(timeout.is_retryable());
+
+        let other = client.synthesize().input("Hello").execute().await.unwrap_err();
+        assert!(matches!(other, TtsError::ApiError { status: 418, .. }));
+        assert!(other.api_details().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_backs_off_when_headers_report_exhaustion() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let limiter = Arc::new(RateLimiter::new().with_requests_per_minute(500));
+        let client = SpeechClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_rate_limiter(limiter);
+        transport.push_response(
+            200,
+            &[
+                ("x-ratelimit-remaining-requests", "0"),
+                ("x-ratelimit-reset-requests", "6s"),
+            ],
+            b"audio".to_vec(),
+        );
+        transport.push_response(200, &[], b"audio".to_vec());
+
+        client.synthesize().input("One").execute().await.unwrap();
+        assert!(transport.sleeps().is_empty());
+
+        client.synthesize().input("Two").execute().await.unwrap();
+        let sleeps = transport.sleeps();
+        assert_eq!(sleeps.len(), 1);
+        assert!(sleeps[0].as_secs_f64() > 5.5 && sleeps[0].as_secs_f64() <= 6.0);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_throttles_retries() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let limiter = Arc::new(RateLimiter::new().with_requests_per_minute(2));
+        let client = SpeechClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_retry_policy(RetryPolicy::default())
+            .with_rate_limiter(limiter.clone());
+        transport.push_response(429, &[("retry-after-ms", "150")], "slow down");
+        transport.push_response(200, &[], b"audio".to_vec());
+
+        client.synthesize().input("Hello").execute().await.unwrap();
+        assert_eq!(transport.requests().len(), 2);
+        assert_eq!(transport.sleeps(), vec![std::time::Duration::from_millis(150)]);
+
+        // Both attempts took a token, so the bucket is empty
+        assert!(limiter.reserve(0.0) > std::time::Duration::from_secs(29));
+    }
+
+    #[tokio::test]
+    async fn test_probe_capabilities_is_throttled_and_guarded() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let limiter = Arc::new(RateLimiter::new().with_requests_per_minute(1));
+        let breaker = Arc::new(CircuitBreaker::new(CircuitBreakerPolicy {
+            minimum_requests: 1,
+            window: 1,
+            ..CircuitBreakerPolicy::default()
+        }));
+        let client = SpeechClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_rate_limiter(limiter.clone())
+            .with_circuit_breaker(breaker.clone());
+        transport.push_response(200, &[], r#"{"data": [{"id": "tts-1"}]}"#);
+        transport.push_response(404, &[], r#"{"error": {"message": "Not found"}}"#);
+
+        // The voices lookup waits for the token the models lookup took
+        let capabilities = client.probe_capabilities().await.unwrap();
+        assert_eq!(capabilities.models, vec!["tts-1"]);
+        assert_eq!(transport.requests().len(), 2);
+        assert_eq!(transport.sleeps().len(), 1);
+        assert!(transport.sleeps()[0] > std::time::Duration::from_secs(59));
+
+        transport.push_response(503, &[], "upstream overloaded");
+        let result = client.probe_capabilities().await;
+        assert!(matches!(result, Err(TtsError::ServerError(_))));
+        assert_eq!(client.circuit_status().unwrap().state, CircuitState::Open);
+        let result = client.probe_capabilities().await;
+        assert!(matches!(result, Err(TtsError::CircuitOpen { .. })));
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[test]
+    fn test_split_text_prefers_paragraphs_then_sentences() {
+        let text = "First paragraph. Still first.\n\nSecond paragraph is here.";
+        assert_eq!(split_text(text, 4096), vec![text]);
+
+        let chunks = split_text(text, 32);
+        assert_eq!(
+            chunks,
+            vec!["First paragraph. Still first.", "Second paragraph is here."]
+        );
+
+        let chunks = split_text("One. Two! Three? Four.", 12);
+        assert_eq!(chunks, vec!["One. Two!", "Three? Four."]);
+
+        // Full-width terminators split without trailing whitespace
+        let chunks = split_text("今日は晴れ。明日は雨。", 8);
+        assert_eq!(chunks, vec!["今日は晴れ。", "明日は雨。"]);
+
+        // Decimal points are not sentence ends, so fall back to words
+        let chunks = split_text("Pi is 3.14159 roughly", 12);
+        assert_eq!(chunks, vec!["Pi is", "3.14159", "roughly"]);
+
+        // A single overlong word is cut at character boundaries
+        let chunks = split_text("abcdefghij", 4);
+        assert_eq!(chunks, vec!["abcd", "efgh", "ij"]);
+        for chunk in split_text(&"word ".repeat(2000), 4096) {
+            assert!(chunk.len() <= 4096);
+        }
+    }
+
+    #[test]
+    fn test_concat_audio_formats() {
+        let fmt = [1u8, 0, 1, 0, 0xC0, 0x5D, 0, 0, 0x80, 0xBB, 0, 0, 2, 0, 16, 0];
+        let first = write_wav(&fmt, &[1, 2, 3, 4]);
+        let mut second = write_wav(&fmt, &[5, 6]);
+        // Streamed WAV leaves the sizes unset
+        second[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+        let joined = concat_audio(&AudioFormat::Wav, vec![first, second]).unwrap();
+        assert_eq!(joined, write_wav(&fmt, &[1, 2, 3, 4, 5, 6]));
+        assert_eq!(&joined[4..8], &(joined.len() as u32 - 8).to_le_bytes());
+
+        let other_fmt = [1u8, 0, 2, 0, 0xC0, 0x5D, 0, 0, 0, 0x77, 1, 0, 4, 0, 16, 0];
+        let mismatched = vec![write_wav(&fmt, &[1, 2]), write_wav(&other_fmt, &[1, 2, 3, 4])];
+        assert!(matches!(
+            concat_audio(&AudioFormat::Wav, mismatched),
+            Err(TtsError::InvalidAudioFormat(_))
+        ));
+
+        let pcm = concat_audio(&AudioFormat::Pcm, vec![vec![1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(pcm, vec![1, 2, 3, 4]);
+
+        let tag = b"ID3\x04\x00\x00\x00\x00\x00\x02ab".to_vec();
+        let frame = vec![0xFF, 0xFB, 0x90, 0x00];
+        let part = [tag.clone(), frame.clone()].concat();
+        let mp3 = concat_audio(&AudioFormat::Mp3, vec![part.clone(), part]).unwrap();
+        assert_eq!(mp3, [tag, frame.clone(), frame].concat());
+
+        assert!(concat_audio(&AudioFormat::Opus, vec![vec![1]]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_synthesize_long_splits_and_joins() {
+        let (client, transport) = mock_client();
+        let sentence = "This sentence is about forty characters. ";
+        let input = sentence.repeat(150);
+        assert!(input.len() > 4096);
+
+        let tag = b"ID3\x04\x00\x00\x00\x00\x00\x02ab".to_vec();
+        transport.push_response(200, &[], [tag.clone(), vec![0xFF, 0xFB, 1]].concat());
+        transport.push_response(
+            200,
+            &[("x-request-id", "req_last")],
+            [tag.clone(), vec![0xFF, 0xFB, 2]].concat(),
+        );
+
+        let response = client
+            .synthesize_long()
+            .input(input.clone())
+            .voice(Voice::Sage)
+            .instructions("Calm")
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(response.audio_data, [tag, vec![0xFF, 0xFB, 1, 0xFF, 0xFB, 2]].concat());
+        assert_eq!(response.metadata.request_id.as_deref(), Some("req_last"));
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        let mut spoken = Vec::new();
+        for request in &requests {
+            let json: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            assert_eq!(json["voice"], "sage");
+            assert_eq!(json["instructions"], "Calm");
+            let piece = json["input"].as_str().unwrap().to_string();
+            assert!(piece.len() <= 4096);
+            assert!(piece.ends_with('.'));
+            spoken.push(piece);
+        }
+        assert_eq!(spoken.join(" "), input.trim());
+
+        // Formats that cannot be joined are rejected before anything is sent
+        let result = client
+            .synthesize_long()
+            .input(input)
+            .response_format(AudioFormat::Opus)
+            .execute()
+            .await;
+        assert!(matches!(result, Err(TtsError::InvalidAudioFormat(_))));
+        assert_eq!(transport.requests().len(), 2);
+
+        // Plain synthesis still enforces the limit
+        let result = client.synthesize().input(sentence.repeat(150)).execute().await;
+        assert!(matches!(result, Err(TtsError::InputTooLong { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_input_limit_counts_characters_not_bytes() {
+        let (client, transport) = mock_client();
+        // 3 bytes per character: 4000 characters are 12000 bytes
+        let japanese = "音".repeat(4000);
+        // Emoji take 4 bytes each
+        let emoji = "🎉".repeat(4096);
+        transport.push_response(200, &[], b"audio".to_vec());
+        transport.push_response(200, &[], b"audio".to_vec());
+
+        client.synthesize().input(japanese.clone()).execute().await.unwrap();
+        client.synthesize().input(emoji).execute().await.unwrap();
+        assert_eq!(transport.requests().len(), 2);
+
+        let too_long = format!("{}{}", japanese, "Привет".repeat(20));
+        let result = client.synthesize().input(too_long).execute().await;
+        match result {
+            Err(TtsError::InputTooLong { characters, bytes }) => {
+                assert_eq!(characters, 4120);
+                assert_eq!(bytes, 12000 + 240);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_split_text_counts_characters() {
+        let text = "こんにちは。".repeat(1000);
+        let chunks = split_text(&text, 4096);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 4096));
+        assert!(chunks[0].len() > 4096);
+        assert_eq!(chunks.concat(), text);
+
+        let chunks = split_text("ééééé", 2);
+        assert_eq!(chunks, vec!["éé", "éé", "é"]);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_sse_speech() {
+        let (client, transport) = mock_client();
+        // Events arrive split across chunks and are decoded as each completes
+        transport.push_chunked_response(
+            200,
+            &[("content-type", "text/event-stream")],
+            [
+                "data: {\"type\":\"speech.audio.delta\",\"audio\":\"AQID\"}\n\ndata: {\"type\"",
+                ":\"speech.audio.delta\",\"audio\":\"BAU=\"}\n",
+                "\ndata: {\"type\":\"speech.audio.done\",\"usage\":",
+                "{\"input_tokens\":14,\"output_tokens\":101,\"total_tokens\":115}}\n\n",
+            ],
+        );
+
+        let mut chunks = Vec::new();
+        let response = client
+            .synthesize()
+            .input("Hello")
+            .model(TtsModel::Gpt4oMiniTts)
+            .response_format(AudioFormat::Pcm)
+            .stream_format(StreamFormat::Sse)
+            .execute_stream(|chunk| chunks.push(chunk.to_vec()))
+            .await
+            .unwrap();
+        assert_eq!(chunks, vec![vec![1, 2, 3], vec![4, 5]]);
+        assert_eq!(response.audio_data, vec![1, 2, 3, 4, 5]);
+        assert_eq!(
+            response.usage,
+            Some(SpeechUsage {
+                input_tokens: 14,
+                output_tokens: 101,
+                total_tokens: 115,
+            })
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_slice(&transport.last_request().unwrap().body).unwrap();
+        assert_eq!(json["stream_format"], "sse");
+
+        // tts-1 models cannot stream events
+        let result = client
+            .synthesize()
+            .input("Hello")
+            .stream_format(StreamFormat::Sse)
+            .execute()
+            .await;
+        assert!(matches!(result, Err(TtsError::InvalidModel(_))));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_raw_audio_speech() {
+        let (client, transport) = mock_client();
+        transport.push_chunked_response(
+            200,
+            &[("content-type", "audio/mpeg")],
+            [vec![0xFF, 0xFB], vec![0x90]],
+        );
+
+        let mut received = Vec::new();
+        let response = client
+            .synthesize()
+            .input("Hello")
+            .execute_stream(|chunk| received.push(chunk.to_vec()))
+            .await
+            .unwrap();
+        assert_eq!(received, vec![vec![0xFF, 0xFB], vec![0x90]]);
+        assert_eq!(response.audio_data, received.concat());
+        assert!(response.usage.is_none());
+
+        let json: serde_json::Value =
+            serde_json::from_slice(&transport.last_request().unwrap().body).unwrap();
+        assert!(json.get("stream_format").is_none());
+    }
+
+    #[test]
+    fn test_decode_speech_stream_event() {
+        assert!(decode_stream_event("[DONE]").unwrap().is_none());
+        assert!(decode_stream_event(r#"{"type":"speech.audio.other"}"#).unwrap().is_none());
+        assert!(decode_stream_event("not json").is_err());
+
+        match decode_stream_event(r#"{"type":"speech.audio.delta","audio":"aGk="}"#).unwrap() {
+            Some(SpeechStreamEvent::Delta(delta)) => assert_eq!(delta.decode().unwrap(), b"hi"),
+            other => panic!("unexpected event: {:?}", other),
+        }
+        match decode_stream_event(r#"{"type":"speech.audio.delta","audio":"!!"}"#).unwrap() {
+            Some(SpeechStreamEvent::Delta(delta)) => {
+                assert!(matches!(delta.decode(), Err(TtsError::ParseError(_))))
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert!(matches!(
+            decode_stream_event(r#"{"type":"speech.audio.done"}"#).unwrap(),
+            Some(SpeechStreamEvent::Done(done)) if done.usage.is_none()
+        ));
+    }
+
+    fn sine(frequency: f64, sample_rate: u32, seconds: f64) -> PcmBuffer {
+        let len = (sample_rate as f64 * seconds) as usize;
+        let samples = (0..len)
+            .map(|i| {
+                let t = i as f64 / sample_rate as f64;
+                ((2.0 * std::f64::consts::PI * frequency * t).sin() * 10000.0) as i16
+            })
+            .collect();
+        PcmBuffer::new(samples, sample_rate)
+    }
+
+    #[test]
+    fn test_pcm_buffer_bytes_and_wav() {
+        let pcm = PcmBuffer::from_speech(&[0x01, 0x00, 0xFF, 0xFF, 0x00, 0x80]).unwrap();
+        assert_eq!(pcm.samples, vec![1, -1, i16::MIN]);
+        assert_eq!(pcm.sample_rate, SPEECH_SAMPLE_RATE);
+        assert_eq!(pcm.to_le_bytes(), vec![0x01, 0x00, 0xFF, 0xFF, 0x00, 0x80]);
+        assert!(PcmBuffer::from_speech(&[0x01]).is_err());
+
+        let wav = pcm.to_wav();
+        assert_eq!(wav.len(), 44 + 6);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[4..8], &42u32.to_le_bytes());
+        assert_eq!(&wav[20..36], fmt_chunk(24_000, 1, 16).as_slice());
+        // Byte rate and block alignment of 24 kHz 16-bit mono
+        assert_eq!(&wav[28..32], &48_000u32.to_le_bytes());
+        assert_eq!(&wav[32..34], &2u16.to_le_bytes());
+        assert_eq!(&wav[40..44], &6u32.to_le_bytes());
+
+        let second = PcmBuffer::new(vec![0; 24_000], SPEECH_SAMPLE_RATE);
+        assert_eq!(second.duration(), std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_pcm_resampling() {
+        let tone = sine(440.0, SPEECH_SAMPLE_RATE, 0.5);
+        for rate in [8_000, 16_000, 48_000] {
+            let resampled = tone.resample(rate);
+            assert_eq!(resampled.sample_rate, rate);
+            assert_eq!(resampled.samples.len(), rate as usize / 2);
+            assert_eq!(resampled.duration(), tone.duration());
+
+            // A 440 Hz tone survives: compare against a tone generated at the target rate
+            let expected = sine(440.0, rate, 0.5);
+            let error = resampled
+                .samples
+                .iter()
+                .zip(&expected.samples)
+                .skip(8)
+                .map(|(a, b)| (*a as i32 - *b as i32).abs())
+                .max()
+                .unwrap();
+            assert!(error < 600, "max error {} at {} Hz", error, rate);
+        }
+
+        // Frequencies above the new Nyquist limit are attenuated
+        let high = sine(7_000.0, SPEECH_SAMPLE_RATE, 0.1).resample(8_000);
+        let peak = high.samples.iter().map(|s| s.unsigned_abs()).max().unwrap();
+        assert!(peak < 5000, "peak {}", peak);
+
+        assert_eq!(tone.resample(SPEECH_SAMPLE_RATE), tone);
+        assert!(PcmBuffer::new(Vec::new(), 24_000).resample(8_000).samples.is_empty());
+    }
+
+    #[test]
+    fn test_resampling_rejects_tones_above_nyquist() {
+        // 10000-amplitude tones above the 4 kHz limit of 8 kHz audio
+        for frequency in [4_500.0, 5_000.0, 7_000.0, 11_000.0] {
+            let resampled = sine(frequency, SPEECH_SAMPLE_RATE, 0.2).resample(8_000);
+            let len = resampled.samples.len();
+            // Away from the edges, where the filter is cut short
+            let peak = resampled.samples[16..len - 16]
+                .iter()
+                .map(|s| s.unsigned_abs())
+                .max()
+                .unwrap();
+            assert!(peak < 100, "peak {} for {} Hz", peak, frequency);
+        }
+
+        // The pass band is left alone
+        let resampled = sine(3_000.0, SPEECH_SAMPLE_RATE, 0.2).resample(8_000);
+        let peak = resampled.samples.iter().map(|s| s.unsigned_abs()).max().unwrap();
+        assert!(peak > 9_000, "peak {}", peak);
+    }
+
+    #[tokio::test]
+    async fn test_speech_response_as_pcm() {
+        let (client, transport) = mock_client();
+        transport.push_response(200, &[], vec![0x10, 0x00, 0x20, 0x00]);
+        let response = client
+            .synthesize()
+            .input("Hello")
+            .response_format(AudioFormat::Pcm)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(response.pcm().unwrap().unwrap().samples, vec![16, 32]);
+
+        transport.push_response(200, &[], b"ID3".to_vec());
+        let response = client.synthesize().input("Hello").execute().await.unwrap();
+        assert!(response.pcm().is_none());
+    }
+
+    #[test]
+    fn test_g711_reference_values() {
+        let mulaw = G711Codec::MuLaw;
+        assert_eq!(mulaw.encode(&[0, i16::MAX, i16::MIN, -1]), vec![0xFF, 0x80, 0x00, 0x7F]);
+        assert_eq!(mulaw.decode(&[0xFF, 0x80, 0x00]).samples, vec![0, 32124, -32124]);
+
+        let alaw = G711Codec::ALaw;
+        assert_eq!(alaw.encode(&[0, i16::MAX, i16::MIN]), vec![0xD5, 0xAA, 0x2A]);
+        assert_eq!(alaw.decode(&[0xD5, 0x55, 0xAA]).samples, vec![8, -8, 32256]);
+    }
+
+    #[test]
+    fn test_g711_round_trip_error_is_bounded() {
+        for codec in [G711Codec::MuLaw, G711Codec::ALaw] {
+            for sample in (i16::MIN..=i16::MAX).step_by(97) {
+                let decoded = codec.decode(&codec.encode(&[sample])).samples[0];
+                let error = (decoded as i32 - sample as i32).abs();
+                assert!(
+                    error <= (sample as i32).abs() / 16 + 16,
+                    "{} {} -> {}",
+                    codec.as_str(),
+                    sample,
+                    decoded
+                );
+            }
+        }
+
+        let wav = G711Codec::MuLaw.decode_to_wav(&[0xFF; 80]);
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[24..28], &TELEPHONY_SAMPLE_RATE.to_le_bytes());
+        assert_eq!(wav.len(), 44 + 160);
+    }
+
+    #[test]
+    fn test_telephony_frames() {
+        let options = TelephonyOptions::alaw();
+        assert_eq!(options.frame_len(), 160);
+        assert_eq!(
+            options.with_frame_duration(std::time::Duration::from_millis(30)).frame_len(),
+            240
+        );
+
+        // 50 ms of speech becomes two full 20 ms frames and a padded one
+        let frames = options.encode_frames(&sine(440.0, SPEECH_SAMPLE_RATE, 0.05));
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|frame| frame.len() == 160));
+        assert!(frames[2][80..].iter().all(|&byte| byte == 0xD5));
+    }
+
+    #[tokio::test]
+    async fn test_execute_telephony_requests_pcm() {
+        let (client, transport) = mock_client();
+        let speech = sine(440.0, SPEECH_SAMPLE_RATE, 0.04);
+        transport.push_response(200, &[("x-request-id", "req_tel")], speech.to_le_bytes());
+
+        let audio = client
+            .synthesize()
+            .input("Hello")
+            .response_format(AudioFormat::Mp3)
+            .execute_telephony(TelephonyOptions::mulaw())
+            .await
+            .unwrap();
+
+        let body: serde_json::Value =
+            serde_json::from_slice(&transport.requests()[0].body).unwrap();
+        assert_eq!(body["response_format"], "pcm");
+        assert_eq!(audio.codec, G711Codec::MuLaw);
+        assert_eq!(audio.frames.len(), 2);
+        assert_eq!(audio.duration(), std::time::Duration::from_millis(40));
+        assert_eq!(audio.metadata.request_id.as_deref(), Some("req_tel"));
+
+        let decoded = G711Codec::MuLaw.decode(&audio.to_bytes());
+        let expected = speech.resample(TELEPHONY_SAMPLE_RATE);
+        let max_error = decoded
+            .samples
+            .iter()
+            .zip(&expected.samples)
+            .map(|(a, b)| (*a as i32 - *b as i32).abs())
+            .max()
+            .unwrap();
+        assert!(max_error < 1100, "max error {}", max_error);
+    }
+
+    #[tokio::test]
+    async fn test_default_and_request_headers() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = SpeechClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_organization("org_default")
+            .with_project("proj_default");
+        transport.push_response(200, &[], b"audio".to_vec());
+
+        client
+            .synthesize()
+            .input("Hello")
+            .organization("org_other")
+            .header("X-Trace", "abc")
+            .execute()
+            .await
+            .unwrap();
+
+        let headers = transport.last_request().unwrap().headers;
+        assert_eq!(headers["OpenAI-Organization"], "org_other");
+        assert_eq!(headers["OpenAI-Project"], "proj_default");
+        assert_eq!(headers["X-Trace"], "abc");
+        assert_eq!(headers["Content-Type"], "application/json");
+    }
+
+    #[tokio::test]
+    async fn test_azure_ad_profile_request() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = SpeechClient::from_profile(ApiProfile::azure_ad_token(
+            "https://my-resource.openai.azure.com",
+            "tts-hd",
+            "2025-03-01-preview",
+            "aad-token",
+        ))
+        .with_transport(transport.clone());
+        transport.push_response(200, &[], b"audio".to_vec());
+
+        client.synthesize().input("Hello").execute().await.unwrap();
+
+        let request = transport.last_request().unwrap();
+        assert_eq!(request.url.path(), "/openai/deployments/tts-hd/audio/speech");
+        assert_eq!(request.url.query(), Some("api-version=2025-03-01-preview"));
+        assert_eq!(request.headers["Authorization"], "Bearer aad-token");
+    }
+
+    #[tokio::test]
+    async fn test_probe_and_validate_against_server() {
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = SpeechClient::from_profile(ApiProfile::keyless("http://tts.local"))
+            .with_transport(transport.clone());
+        transport.push_response(200, &[], r#"{"data": [{"id": "kokoro"}, {"id": "tts-1"}]}"#);
+        transport.push_response(200, &[], r#"{"voices": ["af_heart", "am_adam"]}"#);
+        transport.push_response(200, &[], b"audio".to_vec());
+
+        let capabilities = client.probe_capabilities().await.unwrap();
+        assert_eq!(
+            capabilities.voices,
+            Some(vec!["af_heart".to_string(), "am_adam".to_string()])
+        );
+        assert_eq!(capabilities.formats, None);
+        let client = client.with_capabilities(ServerCapabilities {
+            formats: Some(vec!["mp3".to_string(), "wav".to_string()]),
+            ..capabilities
+        });
+
+        let result = client.synthesize().input("Hi").voice(Voice::Alloy).execute().await;
+        assert!(matches!(result, Err(TtsError::InvalidVoice(_))));
+        let result = client
+            .synthesize()
+            .input("Hi")
+            .model(TtsModel::Gpt4oMiniTts)
+            .voice("af_heart".parse().unwrap())
+            .execute()
+            .await;
+        assert!(matches!(result, Err(TtsError::InvalidModel(_))));
+        let result = client
+            .synthesize()
+            .input("Hi")
+            .voice("af_heart".parse().unwrap())
+            .response_format(AudioFormat::Opus)
+            .execute()
+            .await;
+        assert!(matches!(result, Err(TtsError::InvalidAudioFormat(_))));
+
+        // tts-1 may stream SSE on a server that says it can
+        client
+            .synthesize()
+            .input("Hi")
+            .voice("af_heart".parse().unwrap())
+            .stream_format(StreamFormat::Sse)
+            .execute()
+            .await
+            .unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[1].url.as_str(), "http://tts.local/v1/audio/voices");
+        let body: serde_json::Value = serde_json::from_slice(&requests[2].body).unwrap();
+        assert_eq!(body["voice"], "af_heart");
+        assert!(!requests[2].headers.contains_key("Authorization"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_without_voice_list() {
+        let (client, transport) = mock_client();
+        transport.push_response(200, &[], r#"{"data": [{"id": "tts-1"}]}"#);
+        transport.push_response(404, &[], r#"{"error": {"message": "Not found"}}"#);
+
+        let capabilities = client.probe_capabilities().await.unwrap();
+        assert_eq!(capabilities.models, vec!["tts-1"]);
+        assert_eq!(capabilities.voices, None);
+        assert!(capabilities.supports_voice("alloy"));
+    }
+
+    #[tokio::test]
+    async fn test_text_to_speech_trait() {
+        let (client, transport) = mock_client();
+        transport.push_response(200, &[], b"RIFF-audio".to_vec());
+
+        let backend: &dyn TextToSpeech = &client;
+        let speech = backend
+            .text_to_speech(TextToSpeechRequest {
+                text: "Hello".to_string(),
+                voice: Some("nova".to_string()),
+                model: Some("gpt-4o-mini-tts".to_string()),
+                format: Some("wav".to_string()),
+                instructions: Some("Warmly".to_string()),
+                ..TextToSpeechRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(speech.audio, b"RIFF-audio");
+        assert_eq!(speech.format, "wav");
+
+        let body: serde_json::Value =
+            serde_json::from_slice(&transport.last_request().unwrap().body).unwrap();
+        assert_eq!(body["voice"], "nova");
+        assert_eq!(body["model"], "gpt-4o-mini-tts");
+        assert_eq!(body["instructions"], "Warmly");
+
+        transport.push_response(503, &[], r#"{"error": {"message": "Overloaded"}}"#);
+        let error = backend
+            .text_to_speech(TextToSpeechRequest {
+                text: "Hello".to_string(),
+                ..TextToSpeechRequest::default()
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind, SpeechErrorKind::Unavailable);
+        assert!(error.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_speech_fallback_drops_instructions() {
+        let (client, transport) = mock_client();
+        transport.push_response(503, &[], r#"{"error": {"message": "Overloaded"}}"#);
+        transport.push_response(200, &[], b"audio".to_vec());
+
+        let policy = FallbackPolicy::models([TtsModel::Tts1]);
+        let served = client
+            .synthesize()
+            .input("Hello")
+            .model(TtsModel::Gpt4oMiniTts)
+            .instructions("Cheerfully")
+            .stream_format(StreamFormat::Sse)
+            .execute_with_fallback(&policy)
+            .await
+            .unwrap();
+        assert_eq!(served.response.audio_data, b"audio");
+        assert_eq!(served.served_by.index, 1);
+        assert_eq!(served.served_by.model, "tts-1");
+
+        let requests = transport.requests();
+        let first: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(first["instructions"], "Cheerfully");
+        let second: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(second["model"], "tts-1");
+        assert!(second.get("instructions").is_none());
+        assert!(second.get("stream_format").is_none());
+
+        // The last target's error is returned when every target fails
+        transport.push_response(503, &[], r#"{"error": {"message": "Overloaded"}}"#);
+        transport.push_response(429, &[], r#"{"error": {"message": "Slow down"}}"#);
+        let result = client
+            .synthesize()
+            .input("Hello")
+            .execute_with_fallback(&policy)
+            .await;
+        assert!(matches!(result, Err(TtsError::RateLimited(_))));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_half_open_probe() {
+        let breaker = Arc::new(CircuitBreaker::new(CircuitBreakerPolicy {
+            minimum_requests: 1,
+            open_duration: std::time::Duration::ZERO,
+            ..CircuitBreakerPolicy::default()
+        }));
+        let transport = Arc::new(InMemoryTransport::new());
+        let client = SpeechClient::new("test-key")
+            .with_transport(transport.clone())
+            .with_circuit_breaker(breaker);
+        assert_eq!(client.circuit_status().unwrap().state, CircuitState::Closed);
+
+        transport.push_response(504, &[], "gateway timeout");
+        assert!(client.synthesize().input("Hello").execute().await.is_err());
+        assert_eq!(client.circuit_status().unwrap().state, CircuitState::Open);
+
+        // The open period has passed, so the next request goes out as a probe
+        transport.push_response(200, &[], b"audio".to_vec());
+        let response = client.synthesize().input("Hello").execute().await.unwrap();
+        assert_eq!(response.audio_data, b"audio");
+        assert_eq!(client.circuit_status().unwrap().state, CircuitState::Closed);
+        assert_eq!(transport.requests().len(), 2);
+    }
+}
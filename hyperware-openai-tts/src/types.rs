@@ -1,28 +1,58 @@
+use crate::error::TtsError;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use hyperware_openai_core::endpoint::ApiProfile;
+use hyperware_openai_core::headers::CustomHeaders;
+use hyperware_openai_core::metadata::ResponseMetadata;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
+/// Speech model. Unknown ids are kept in `Custom` so new models can be used
+/// without a crate release.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TtsModel {
-    #[serde(rename = "tts-1")]
     Tts1,
-    #[serde(rename = "tts-1-hd")]
     Tts1Hd,
-    #[serde(rename = "gpt-4o-mini-tts")]
     Gpt4oMiniTts,
+    Custom(String),
 }
 
 impl TtsModel {
+    pub const KNOWN: &'static [TtsModel] =
+        &[TtsModel::Tts1, TtsModel::Tts1Hd, TtsModel::Gpt4oMiniTts];
+
     pub fn as_str(&self) -> &str {
         match self {
             TtsModel::Tts1 => "tts-1",
             TtsModel::Tts1Hd => "tts-1-hd",
             TtsModel::Gpt4oMiniTts => "gpt-4o-mini-tts",
+            TtsModel::Custom(name) => name,
         }
     }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, TtsModel::Custom(_))
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+impl FromStr for TtsModel {
+    type Err = TtsError;
+
+    /// Known ids match case-insensitively; anything else becomes `Custom`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(TtsError::InvalidModel("model name is empty".to_string()));
+        }
+        Ok(TtsModel::KNOWN
+            .iter()
+            .find(|model| model.as_str().eq_ignore_ascii_case(s))
+            .cloned()
+            .unwrap_or_else(|| TtsModel::Custom(s.to_string())))
+    }
+}
+
+/// Voice used for synthesis. Unknown voices are kept in `Custom`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Voice {
     Alloy,
     Ash,
@@ -35,9 +65,24 @@ pub enum Voice {
     Sage,
     Shimmer,
     Verse,
+    Custom(String),
 }
 
 impl Voice {
+    pub const KNOWN: &'static [Voice] = &[
+        Voice::Alloy,
+        Voice::Ash,
+        Voice::Ballad,
+        Voice::Coral,
+        Voice::Echo,
+        Voice::Fable,
+        Voice::Onyx,
+        Voice::Nova,
+        Voice::Sage,
+        Voice::Shimmer,
+        Voice::Verse,
+    ];
+
     pub fn as_str(&self) -> &str {
         match self {
             Voice::Alloy => "alloy",
@@ -51,22 +96,55 @@ impl Voice {
             Voice::Sage => "sage",
             Voice::Shimmer => "shimmer",
             Voice::Verse => "verse",
+            Voice::Custom(name) => name,
         }
     }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Voice::Custom(_))
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+impl FromStr for Voice {
+    type Err = TtsError;
+
+    /// Known voices match case-insensitively; anything else becomes `Custom`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(TtsError::InvalidVoice("voice name is empty".to_string()));
+        }
+        Ok(Voice::KNOWN
+            .iter()
+            .find(|voice| voice.as_str().eq_ignore_ascii_case(s))
+            .cloned()
+            .unwrap_or_else(|| Voice::Custom(s.to_string())))
+    }
+}
+
+/// Output audio format. Unknown formats are kept in `Custom`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub enum AudioFormat {
+    #[default]
     Mp3,
     Opus,
     Aac,
     Flac,
     Wav,
     Pcm,
+    Custom(String),
 }
 
 impl AudioFormat {
+    pub const KNOWN: &'static [AudioFormat] = &[
+        AudioFormat::Mp3,
+        AudioFormat::Opus,
+        AudioFormat::Aac,
+        AudioFormat::Flac,
+        AudioFormat::Wav,
+        AudioFormat::Pcm,
+    ];
+
     pub fn as_str(&self) -> &str {
         match self {
             AudioFormat::Mp3 => "mp3",
@@ -75,16 +153,34 @@ impl AudioFormat {
             AudioFormat::Flac => "flac",
             AudioFormat::Wav => "wav",
             AudioFormat::Pcm => "pcm",
+            AudioFormat::Custom(name) => name,
         }
     }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, AudioFormat::Custom(_))
+    }
 }
 
-impl Default for AudioFormat {
-    fn default() -> Self {
-        AudioFormat::Mp3
+impl FromStr for AudioFormat {
+    type Err = TtsError;
+
+    /// Known formats match case-insensitively; anything else becomes `Custom`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(TtsError::InvalidAudioFormat("audio format is empty".to_string()));
+        }
+        Ok(AudioFormat::KNOWN
+            .iter()
+            .find(|format| format.as_str().eq_ignore_ascii_case(s))
+            .cloned()
+            .unwrap_or_else(|| AudioFormat::Custom(s.to_string())))
     }
 }
 
+hyperware_openai_core::impl_wire_name!(TtsModel, Voice, AudioFormat);
+
 #[derive(Debug, Clone)]
 pub struct SpeechRequest {
     pub input: String,
@@ -93,6 +189,11 @@ pub struct SpeechRequest {
     pub instructions: Option<String>,
     pub response_format: Option<AudioFormat>,
     pub speed: Option<f32>,
+    pub stream_format: Option<StreamFormat>,
+    /// Extra headers, replacing client defaults of the same name.
+    pub headers: CustomHeaders,
+    /// Endpoint and credentials for this request; the client's when `None`.
+    pub profile: Option<ApiProfile>,
 }
 
 impl Default for SpeechRequest {
@@ -104,6 +205,9 @@ impl Default for SpeechRequest {
             instructions: None,
             response_format: None,
             speed: None,
+            stream_format: None,
+            headers: CustomHeaders::new(),
+            profile: None,
         }
     }
 }
@@ -119,6 +223,8 @@ pub struct SpeechRequestJson {
     pub response_format: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub speed: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_format: Option<String>,
 }
 
 impl From<SpeechRequest> for SpeechRequestJson {
@@ -130,6 +236,7 @@ impl From<SpeechRequest> for SpeechRequestJson {
             instructions: req.instructions,
             response_format: req.response_format.map(|f| f.as_str().to_string()),
             speed: req.speed,
+            stream_format: req.stream_format.map(|f| f.as_str().to_string()),
         }
     }
 }
@@ -138,17 +245,71 @@ impl From<SpeechRequest> for SpeechRequestJson {
 pub struct SpeechResponse {
     pub audio_data: Vec<u8>,
     pub format: AudioFormat,
+    /// Token usage, reported by `speech.audio.done` when streaming with SSE.
+    pub usage: Option<SpeechUsage>,
+    /// Request id, processing time and rate-limit state from the response headers.
+    pub metadata: ResponseMetadata,
+}
+
+/// How the speech endpoint delivers audio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// Raw audio bytes with chunked transfer encoding (the API default).
+    Audio,
+    /// Server-sent `speech.audio.delta` events carrying base64 audio, then
+    /// `speech.audio.done` with usage. Not supported by tts-1 and tts-1-hd.
+    Sse,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ApiErrorResponse {
-    pub error: ApiErrorDetail,
+impl StreamFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamFormat::Audio => "audio",
+            StreamFormat::Sse => "sse",
+        }
+    }
+}
+
+/// Event of a speech response sent with `stream_format: "sse"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SpeechStreamEvent {
+    #[serde(rename = "speech.audio.delta")]
+    Delta(SpeechAudioDelta),
+    #[serde(rename = "speech.audio.done")]
+    Done(SpeechAudioDone),
+}
+
+impl SpeechStreamEvent {
+    pub(crate) const KNOWN_TYPES: [&'static str; 2] = ["speech.audio.delta", "speech.audio.done"];
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeechAudioDelta {
+    /// Base64-encoded audio in the requested format.
+    pub audio: String,
+}
+
+impl SpeechAudioDelta {
+    pub fn decode(&self) -> Result<Vec<u8>, TtsError> {
+        BASE64_STANDARD
+            .decode(&self.audio)
+            .map_err(|e| TtsError::ParseError(format!("invalid base64 audio: {}", e)))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeechAudioDone {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<SpeechUsage>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ApiErrorDetail {
-    pub message: String,
-    #[serde(rename = "type")]
-    pub error_type: Option<String>,
-    pub code: Option<String>,
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpeechUsage {
+    #[serde(default)]
+    pub input_tokens: u32,
+    #[serde(default)]
+    pub output_tokens: u32,
+    #[serde(default)]
+    pub total_tokens: u32,
 }
\ No newline at end of file